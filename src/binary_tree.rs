@@ -1,7 +1,9 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
+use crate::Tree;
+
 /// ```json
 /// {
 ///     "value": "A",
@@ -49,7 +51,9 @@ where
     pub fn from_vec(vec: &[T]) -> Self {
         let (root, rest): (&T, &[T]) = vec.split_first().unwrap();
         let mut tree = BinaryTree::new(*root);
-        rest.iter().for_each(|value: &T| tree.insert_breadth_first(*value));
+        for value in rest {
+            tree.insert_breadth_first(*value);
+        }
         tree
     }
 
@@ -119,24 +123,18 @@ where
         loop {
             let BinaryTree { ref mut left, ref mut right, .. } = queue.pop_back().unwrap();
 
-            match left {
-                Some(node) => {
-                    queue.push_front(node);
-                }
-                None => {
-                    *left = Some(Box::new(BinaryTree::new(new_value)));
-                    return;
-                }
+            if let Some(node) = left {
+                queue.push_front(node);
+            } else {
+                *left = Some(Box::new(BinaryTree::new(new_value)));
+                return;
             }
 
-            match right {
-                Some(node) => {
-                    queue.push_front(node);
-                }
-                None => {
-                    *right = Some(Box::new(BinaryTree::new(new_value)));
-                    return;
-                }
+            if let Some(node) = right {
+                queue.push_front(node);
+            } else {
+                *right = Some(Box::new(BinaryTree::new(new_value)));
+                return;
             }
         }
     }
@@ -159,6 +157,19 @@ where
     }
 }
 
+impl<T> BinaryTree<T>
+where
+    T: Display,
+{
+    /// Converts the binary tree into a [`Tree`] that can be rendered on the terminal.
+    ///
+    /// The left child is always listed before the right child.
+    pub fn to_tree(&self) -> Tree<String> {
+        Tree::new(self.value.to_string())
+            .with_leaves([&self.left, &self.right].into_iter().flatten().map(|node| node.to_tree()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::{assert_eq, assert_ne};
@@ -258,4 +269,17 @@ mod tests {
             .with_right(BinaryTree::new(3).with_left(BinaryTree::new(6)));
         assert_eq!(tree, expect);
     }
+
+    #[test]
+    fn it_convert_to_term_tree() {
+        let tree = BinaryTree::from_vec(&[1, 2, 3, 4]).to_tree();
+        assert_eq!(
+            format!("{}", tree),
+            r#"1
+├── 2
+│   └── 4
+└── 3
+"#
+        );
+    }
 }
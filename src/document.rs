@@ -0,0 +1,262 @@
+//! Trees of YAML, TOML and JSON documents, for eyeballing the structure of config files and
+//! payloads.
+
+use serde_json::Value as JsonValue;
+use snafu::{ResultExt, Snafu};
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::Tree;
+
+// ---------------------------------------------------------
+
+#[derive(Debug, Snafu)]
+pub enum DocumentError {
+    #[snafu(display("invalid YAML: {source}"))]
+    Yaml { source: yaml_rust::ScanError },
+    #[snafu(display("invalid TOML: {source}"))]
+    Toml { source: toml::de::Error },
+}
+
+/// How [`Tree::from_json`] shows values.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{JsonOptions, Tree};
+///
+/// let json = serde_json::json!({ "id": 7, "tags": ["a", "b", "c"], "bio": "a long story" });
+/// let options = JsonOptions { types: true, max_string: Some(6), max_items: Some(2) };
+/// assert_eq!(
+///     Tree::from_json(&json, &options).to_string(),
+///     ". (object)\n├── id (number): 7\n├── tags (array[3])\n│   ├── [0] (string): \"a\"\n\
+///      │   ├── [1] (string): \"b\"\n│   └── … 1 more item\n└── bio (string): \"a long…\"\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Adds the type of each value after its key, like `tags (array[3])`.
+    pub types: bool,
+    /// Cuts strings longer than this many characters, ending them in `…`.
+    pub max_string: Option<usize>,
+    /// Shows this many items of each array, the rest are summed up as `… 37 more items`.
+    pub max_items: Option<usize>,
+}
+
+/// A document value, before it's turned into nodes.
+enum Value {
+    Scalar(String),
+    Map(Vec<(String, Value)>),
+    Sequence(Vec<Value>),
+}
+
+impl Value {
+    /// The node for this value, labeled `key: value` for scalars and `key` otherwise.
+    fn into_tree(self, key: String) -> Tree<String> {
+        match self {
+            Value::Scalar(value) => Tree::new(format!("{}: {}", key, value)),
+            Value::Map(entries) if entries.is_empty() => Tree::new(format!("{}: {{}}", key)),
+            Value::Sequence(items) if items.is_empty() => Tree::new(format!("{}: []", key)),
+            value => {
+                let mut tree = Tree::new(key);
+                tree.leaves = value.into_leaves();
+                tree
+            }
+        }
+    }
+
+    fn into_leaves(self) -> Vec<Tree<String>> {
+        match self {
+            Value::Scalar(_) => Vec::new(),
+            Value::Map(entries) => {
+                entries.into_iter().map(|(key, value)| value.into_tree(key)).collect()
+            }
+            Value::Sequence(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| item.into_tree(format!("[{}]", i)))
+                .collect(),
+        }
+    }
+
+    /// The tree of a whole document, the root being a lone scalar or `.`.
+    fn into_root(self) -> Tree<String> {
+        match self {
+            Value::Scalar(value) => Tree::new(value),
+            value => {
+                let mut tree = Tree::new(".".to_owned());
+                tree.leaves = value.into_leaves();
+                tree
+            }
+        }
+    }
+}
+
+impl From<Yaml> for Value {
+    fn from(yaml: Yaml) -> Self {
+        match yaml {
+            Yaml::Hash(hash) => Value::Map(
+                hash.into_iter().map(|(key, value)| (scalar(key), value.into())).collect(),
+            ),
+            Yaml::Array(items) => Value::Sequence(items.into_iter().map(Into::into).collect()),
+            yaml => Value::Scalar(scalar(yaml)),
+        }
+    }
+}
+
+/// A YAML scalar as written, collections only show up here as keys.
+fn scalar(yaml: Yaml) -> String {
+    match yaml {
+        Yaml::String(s) | Yaml::Real(s) => s,
+        Yaml::Integer(i) => i.to_string(),
+        Yaml::Boolean(b) => b.to_string(),
+        Yaml::Null | Yaml::BadValue => "null".to_owned(),
+        Yaml::Alias(_) => "*alias".to_owned(),
+        Yaml::Array(items) => {
+            format!("[{}]", items.into_iter().map(scalar).collect::<Vec<_>>().join(", "))
+        }
+        Yaml::Hash(hash) => {
+            let entries = hash.into_iter().map(|(k, v)| format!("{}: {}", scalar(k), scalar(v)));
+            format!("{{{}}}", entries.collect::<Vec<_>>().join(", "))
+        }
+    }
+}
+
+impl From<toml::Value> for Value {
+    fn from(toml: toml::Value) -> Self {
+        match toml {
+            toml::Value::Table(table) => {
+                Value::Map(table.into_iter().map(|(key, value)| (key, value.into())).collect())
+            }
+            toml::Value::Array(items) => {
+                Value::Sequence(items.into_iter().map(Into::into).collect())
+            }
+            toml::Value::String(s) => Value::Scalar(s),
+            toml::Value::Integer(i) => Value::Scalar(i.to_string()),
+            toml::Value::Float(f) => Value::Scalar(format!("{:?}", f)),
+            toml::Value::Boolean(b) => Value::Scalar(b.to_string()),
+            toml::Value::Datetime(datetime) => Value::Scalar(datetime.to_string()),
+        }
+    }
+}
+
+// ---------------------------------------------------------
+
+impl Tree<String> {
+    /// Reads a YAML document: maps become their keys, sequences become `[0]`, `[1]`… and
+    /// scalars are shown inline as `key: value`.
+    ///
+    /// The root is `.`, with a `[0]`, `[1]`… child per document if there are several of them, or
+    /// the value of a document that's a lone scalar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let yaml = "name: treeleaf\nauthors:\n  - Ada\n  - Grace\nrelease: {}\n";
+    /// assert_eq!(
+    ///     Tree::from_yaml(yaml).unwrap().to_string(),
+    ///     ".\n├── name: treeleaf\n├── authors\n│   ├── [0]: Ada\n│   └── [1]: Grace\n└── release: {}\n"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `text` isn't valid YAML.
+    pub fn from_yaml(text: &str) -> Result<Self, DocumentError> {
+        let mut documents = YamlLoader::load_from_str(text).context(YamlSnafu)?;
+        let value = match documents.len() {
+            0 => Value::Map(Vec::new()),
+            1 => documents.remove(0).into(),
+            _ => Value::Sequence(documents.into_iter().map(Into::into).collect()),
+        };
+        Ok(value.into_root())
+    }
+
+    /// Reads a TOML document like [`Tree::from_yaml`], tables becoming their keys and arrays
+    /// `[0]`, `[1]`… in the order they're written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let toml = "[package]\nname = \"treeleaf\"\nkeywords = [\"cli\", \"tree\"]\n";
+    /// assert_eq!(
+    ///     Tree::from_toml(toml).unwrap().to_string(),
+    ///     ".\n└── package\n    ├── name: treeleaf\n    └── keywords\n        ├── [0]: cli\n        └── [1]: tree\n"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `text` isn't valid TOML.
+    pub fn from_toml(text: &str) -> Result<Self, DocumentError> {
+        let table: toml::Table = toml::from_str(text).context(TomlSnafu)?;
+        Ok(Value::from(toml::Value::Table(table)).into_root())
+    }
+
+    /// Shows a JSON value like [`Tree::from_yaml`], with strings quoted so that they stand out
+    /// from numbers, and object keys in the order they're written.
+    ///
+    /// The root is `.`, like a key for the whole document, so a scalar document is `.: 42`.
+    pub fn from_json(value: &JsonValue, options: &JsonOptions) -> Self {
+        json_tree(".".to_owned(), value, options)
+    }
+}
+
+/// The node for a JSON value, labeled `key: value` for scalars and `key` otherwise.
+fn json_tree(key: String, value: &JsonValue, options: &JsonOptions) -> Tree<String> {
+    let key = match options.types {
+        true => format!("{} ({})", key, json_type(value)),
+        false => key,
+    };
+    let leaves = match value {
+        JsonValue::Array(items) if items.is_empty() => return Tree::new(format!("{}: []", key)),
+        JsonValue::Object(map) if map.is_empty() => return Tree::new(format!("{}: {{}}", key)),
+        JsonValue::Array(items) => {
+            let shown = options.max_items.map_or(items.len(), |max| max.min(items.len()));
+            let mut leaves: Vec<_> = items
+                .iter()
+                .take(shown)
+                .enumerate()
+                .map(|(i, item)| json_tree(format!("[{}]", i), item, options))
+                .collect();
+            match items.len() - shown {
+                0 => {}
+                1 => leaves.push(Tree::new("… 1 more item".to_owned())),
+                more => leaves.push(Tree::new(format!("… {} more items", more))),
+            }
+            leaves
+        }
+        JsonValue::Object(map) => {
+            map.iter().map(|(key, value)| json_tree(key.clone(), value, options)).collect()
+        }
+        scalar => return Tree::new(format!("{}: {}", key, json_scalar(scalar, options))),
+    };
+    let mut tree = Tree::new(key);
+    tree.leaves = leaves;
+    tree
+}
+
+fn json_type(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_owned(),
+        JsonValue::Bool(_) => "boolean".to_owned(),
+        JsonValue::Number(_) => "number".to_owned(),
+        JsonValue::String(_) => "string".to_owned(),
+        JsonValue::Array(items) => format!("array[{}]", items.len()),
+        JsonValue::Object(_) => "object".to_owned(),
+    }
+}
+
+/// A scalar as JSON, strings cut to [`JsonOptions::max_string`] characters.
+fn json_scalar(value: &JsonValue, options: &JsonOptions) -> String {
+    match (value, options.max_string) {
+        (JsonValue::String(s), Some(max)) if s.chars().count() > max => {
+            let cut: String = s.chars().take(max).chain(['…']).collect();
+            JsonValue::String(cut).to_string()
+        }
+        _ => value.to_string(),
+    }
+}
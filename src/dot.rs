@@ -0,0 +1,244 @@
+//! [Graphviz](https://graphviz.org/doc/info/lang.html) DOT graphs of trees, for rendering with
+//! `dot`.
+
+use std::fmt::{self, Display, Write};
+
+use crate::{style::web_color, BinaryTree, Direction, Tree, TreeStyle};
+
+// ---------------------------------------------------------
+
+/// How a DOT graph is laid out and how its nodes look.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{Direction, DotOptions, Tree};
+///
+/// let options = DotOptions {
+///     direction: Direction::LeftRight,
+///     node_attributes: vec![("shape".into(), "box".into())],
+///     ..Default::default()
+/// };
+/// let tree = Tree::new("foo").with_leaves(["bar"]);
+/// assert_eq!(
+///     tree.to_dot(&options),
+///     "digraph tree {\n    rankdir=LR;\n    node [shape=\"box\"];\n    n [label=\"foo\"];\n    \
+///      n_0 [label=\"bar\"];\n    n -> n_0;\n}\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Which way the ranks grow, written as `rankdir`.
+    pub direction: Direction,
+    /// Whether node styles become colors, fills and bold or underlined labels.
+    pub styles: bool,
+    /// Attributes shared by every node, such as `("shape", "box")`.
+    pub node_attributes: Vec<(String, String)>,
+}
+
+impl DotOptions {
+    fn rankdir(&self) -> &'static str {
+        match self.direction {
+            Direction::TopDown => "TB",
+            Direction::BottomUp => "BT",
+            Direction::LeftRight => "LR",
+            Direction::RightLeft => "RL",
+        }
+    }
+
+    fn write_header<W: Write>(&self, out: &mut W) -> fmt::Result {
+        writeln!(out, "digraph tree {{")?;
+        writeln!(out, "    rankdir={};", self.rankdir())?;
+        if !self.node_attributes.is_empty() {
+            let attributes = self
+                .node_attributes
+                .iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, Quoted(value)));
+            writeln!(out, "    node [{}];", attributes.collect::<Vec<_>>().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Draws the tree as a Graphviz DOT graph.
+    ///
+    /// Node IDs follow the path from the root like [`Tree::to_mermaid`]. With
+    /// [`DotOptions::styles`], a style's foreground sets `color` and `fontcolor`, its background
+    /// fills the node, and `bold` or `underlined` switch to an HTML label.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{DotOptions, Tree};
+    ///
+    /// let options = DotOptions { styles: true, ..Default::default() };
+    /// let tree = Tree::new("src").with_style("bold.blue".parse().unwrap());
+    /// assert!(tree.to_dot(&options).contains(
+    ///     "n [label=<<B>src</B>>, color=\"blue\", fontcolor=\"blue\"];"
+    /// ));
+    /// ```
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let mut out = String::new();
+        // Writing to a `String` only fails if a label's `Display` does.
+        let _ = options
+            .write_header(&mut out)
+            .and_then(|()| write_tree(&mut out, self, "n", options))
+            .and_then(|()| writeln!(out, "}}"));
+        out
+    }
+}
+
+fn write_tree<D, W>(out: &mut W, tree: &Tree<D>, id: &str, options: &DotOptions) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let style = tree.style.as_ref().filter(|_| options.styles);
+    writeln!(out, "    {} [{}];", id, Attributes { label: &tree.root, style })?;
+    for (i, leaf) in tree.leaves.iter().enumerate() {
+        let child = format!("{}_{}", id, i);
+        write_tree(out, leaf, &child, options)?;
+        writeln!(out, "    {} -> {};", id, child)?;
+    }
+    Ok(())
+}
+
+impl<T> BinaryTree<T>
+where
+    T: Display,
+{
+    /// Draws the tree as a Graphviz DOT graph.
+    ///
+    /// Node IDs follow the path from the root like [`BinaryTree::to_mermaid`]. An only child is
+    /// paired with an invisible node on its missing side, so that left and right stay apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{BinaryTree, DotOptions};
+    ///
+    /// let tree = BinaryTree::new(1).with_right(BinaryTree::new(3));
+    /// assert_eq!(
+    ///     tree.to_dot(&DotOptions::default()),
+    ///     "digraph tree {\n    rankdir=TB;\n    ordering=out;\n    n [label=\"1\"];\n    \
+    ///      nL [label=\"\", style=invis];\n    n -> nL [style=invis];\n    \
+    ///      nR [label=\"3\"];\n    n -> nR;\n}\n"
+    /// );
+    /// ```
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let mut out = String::new();
+        let _ = options
+            .write_header(&mut out)
+            .and_then(|()| writeln!(out, "    ordering=out;"))
+            .and_then(|()| write_children(&mut out, self, "n"))
+            .and_then(|()| writeln!(out, "}}"));
+        out
+    }
+}
+
+fn write_children<T, W>(out: &mut W, tree: &BinaryTree<T>, id: &str) -> fmt::Result
+where
+    T: Display,
+    W: Write,
+{
+    writeln!(out, "    {} [{}];", id, Attributes { label: &tree.value, style: None })?;
+    if tree.left.is_none() && tree.right.is_none() {
+        return Ok(());
+    }
+    for (side, node) in [("L", &tree.left), ("R", &tree.right)] {
+        let child = format!("{}{}", id, side);
+        if let Some(node) = node {
+            write_children(out, node, &child)?;
+            writeln!(out, "    {} -> {};", id, child)?;
+        } else {
+            writeln!(out, "    {} [label=\"\", style=invis];", child)?;
+            writeln!(out, "    {} -> {} [style=invis];", id, child)?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------
+
+/// The attributes of a node: its label and what its style turns into.
+struct Attributes<'a, D> {
+    label: &'a D,
+    style: Option<&'a TreeStyle>,
+}
+
+impl<D> Display for Attributes<'_, D>
+where
+    D: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = self.label.to_string();
+        let Some(style) = self.style else {
+            return write!(f, "label=\"{}\"", Quoted(&label));
+        };
+
+        let tags: Vec<&str> = [("bold", "B"), ("underlined", "U")]
+            .iter()
+            .filter(|(attribute, _)| style.has_attribute(attribute))
+            .map(|(_, tag)| *tag)
+            .collect();
+        if tags.is_empty() {
+            write!(f, "label=\"{}\"", Quoted(&label))?;
+        } else {
+            f.write_str("label=<")?;
+            tags.iter().try_for_each(|tag| write!(f, "<{}>", tag))?;
+            write!(f, "{}", Html(&label))?;
+            tags.iter().rev().try_for_each(|tag| write!(f, "</{}>", tag))?;
+            f.write_str(">")?;
+        }
+
+        if let Some(color) = style.foreground().and_then(web_color) {
+            write!(f, ", color=\"{0}\", fontcolor=\"{0}\"", color)?;
+        }
+        if let Some(color) = style.background().and_then(web_color) {
+            write!(f, ", style=filled, fillcolor=\"{}\"", color)?;
+        }
+        Ok(())
+    }
+}
+
+/// Text inside a double-quoted DOT string.
+struct Quoted<'a>(&'a str);
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text inside a DOT HTML label.
+struct Html<'a>(&'a str);
+
+impl Display for Html<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\n' => f.write_str("<BR/>")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
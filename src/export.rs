@@ -0,0 +1,278 @@
+//! Markdown, AsciiDoc and CSV exports of trees, for pasting into docs and spreadsheets.
+
+use std::fmt::{self, Display, Write};
+
+use strum::{Display as StrumDisplay, EnumString, EnumVariantNames};
+
+use crate::{render, Tree};
+
+// ---------------------------------------------------------
+
+/// How [`Tree::to_markdown`] and [`Tree::to_asciidoc`] write a tree.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{MarkupStyle, Tree};
+///
+/// let tree = Tree::new("foo").with_leaves(["bar"]);
+/// assert_eq!(tree.to_markdown(MarkupStyle::List), "- foo\n  - bar\n");
+/// assert_eq!(tree.to_markdown(MarkupStyle::Code), "```text\nfoo\n└── bar\n```\n");
+/// ```
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, EnumString, EnumVariantNames, StrumDisplay,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum MarkupStyle {
+    /// Nested lists, one item per node, so that the tree is rendered by the document's own
+    /// styles.
+    #[default]
+    List,
+    /// The terminal rendering in a code block, without colors.
+    Code,
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Writes the tree as Markdown, either as nested `-` lists or as a fenced `text` block.
+    ///
+    /// Lists hold every node whatever [`RenderOptions::max_depth`] says, with Markdown syntax
+    /// escaped, hard line breaks between the lines of a label and annotations in parentheses.
+    ///
+    /// [`RenderOptions::max_depth`]: crate::RenderOptions::max_depth
+    pub fn to_markdown(&self, style: MarkupStyle) -> String {
+        let mut out = String::new();
+        // Writing to a `String` only fails if a label's `Display` does.
+        let _ = match style {
+            MarkupStyle::List => write_markdown_item(&mut out, self, 0),
+            MarkupStyle::Code => write_code_block(&mut out, self, '`', 3, "text"),
+        };
+        out
+    }
+
+    /// Writes the tree as AsciiDoc, either as nested `*` lists or as a `----` listing block.
+    ///
+    /// Lists are written like [`Tree::to_markdown`], labels with formatting marks going through
+    /// `pass:c[…]` so that they show as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{MarkupStyle, Tree};
+    ///
+    /// let tree = Tree::new("src").with_leaves(["main.rs", "__init__.py"]);
+    /// assert_eq!(
+    ///     tree.to_asciidoc(MarkupStyle::List),
+    ///     "* src\n** main.rs\n** pass:c[__init__.py]\n"
+    /// );
+    /// ```
+    pub fn to_asciidoc(&self, style: MarkupStyle) -> String {
+        let mut out = String::new();
+        let _ = match style {
+            MarkupStyle::List => write_asciidoc_item(&mut out, self, 0),
+            MarkupStyle::Code => write_code_block(&mut out, self, '-', 4, ""),
+        };
+        out
+    }
+
+    /// Writes one CSV row per node in pre-order, under an `id,parent_id,depth,path,label`
+    /// header.
+    ///
+    /// IDs count nodes from `0`, the root's `parent_id` is empty and `path` joins the labels
+    /// from the root with `/`. Fields are quoted as in RFC 4180 when they need to be, and rows
+    /// end with `\n`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let bin = Tree::new("bin").with_leaves(["a, b"]);
+    /// let tree = Tree::new("src").with_leaves([bin, Tree::new("lib.rs")]);
+    /// assert_eq!(
+    ///     tree.to_csv(),
+    ///     "id,parent_id,depth,path,label\n0,,0,src,src\n1,0,1,src/bin,bin\n\
+    ///      2,1,2,\"src/bin/a, b\",\"a, b\"\n3,0,1,src/lib.rs,lib.rs\n"
+    /// );
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("id,parent_id,depth,path,label\n");
+        let mut rows = CsvRows { out: &mut out, next_id: 0 };
+        let _ = rows.write(self, None, "", 0);
+        out
+    }
+}
+
+// ---------------------------------------------------------
+
+fn write_markdown_item<D, W>(out: &mut W, tree: &Tree<D>, depth: usize) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let indent = "  ".repeat(depth);
+    let text = item_text(tree);
+    for (i, line) in text.lines().enumerate() {
+        match i {
+            0 => write!(out, "{}- ", indent)?,
+            // A backslash at the end of a line is a hard line break.
+            _ => write!(out, "\\\n{}  ", indent)?,
+        }
+        write!(out, "{}", MarkdownEscaped(line))?;
+    }
+    writeln!(out)?;
+    tree.leaves.iter().try_for_each(|leaf| write_markdown_item(out, leaf, depth + 1))
+}
+
+fn write_asciidoc_item<D, W>(out: &mut W, tree: &Tree<D>, depth: usize) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let text = item_text(tree);
+    for (i, line) in text.lines().enumerate() {
+        match i {
+            0 => write!(out, "{} ", "*".repeat(depth + 1))?,
+            _ => writeln!(out, " +")?,
+        }
+        write!(out, "{}", AsciidocEscaped(line))?;
+    }
+    writeln!(out)?;
+    tree.leaves.iter().try_for_each(|leaf| write_asciidoc_item(out, leaf, depth + 1))
+}
+
+/// The label of a list item followed by the annotation, never empty so that the item has a
+/// line.
+fn item_text<D>(tree: &Tree<D>) -> String
+where
+    D: Display,
+{
+    let mut text = tree.root.to_string();
+    if let Some(annotation) = &tree.annotation {
+        text = format!("{} ({})", text, annotation);
+    }
+    if text.is_empty() {
+        text.push(' ');
+    }
+    text
+}
+
+/// Writes the rendering between two lines of at least `min` times `fence`, more if a line of
+/// the rendering could end the block early.
+fn write_code_block<D, W>(
+    out: &mut W,
+    tree: &Tree<D>,
+    fence: char,
+    min: usize,
+    info: &str,
+) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let mut rendered = String::new();
+    render::render_plain(tree, &mut rendered)?;
+    let longest = rendered
+        .lines()
+        .map(|line| line.trim_start().chars().take_while(|&c| c == fence).count())
+        .max()
+        .unwrap_or_default();
+    let delimiter = fence.to_string().repeat(min.max(longest + 1));
+    write!(out, "{}{}\n{}{}\n", delimiter, info, rendered, delimiter)
+}
+
+struct CsvRows<'a> {
+    out: &'a mut String,
+    next_id: usize,
+}
+
+impl CsvRows<'_> {
+    fn write<D>(
+        &mut self,
+        tree: &Tree<D>,
+        parent: Option<usize>,
+        path: &str,
+        depth: usize,
+    ) -> fmt::Result
+    where
+        D: Display,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let label = tree.root.to_string();
+        let path = match parent {
+            Some(_) => format!("{}/{}", path, label),
+            None => label.clone(),
+        };
+        let parent = parent.map(|parent| parent.to_string()).unwrap_or_default();
+        writeln!(self.out, "{},{},{},{},{}", id, parent, depth, CsvField(&path), CsvField(&label))?;
+        tree.leaves.iter().try_for_each(|leaf| self.write(leaf, Some(id), &path, depth + 1))
+    }
+}
+
+// ---------------------------------------------------------
+
+/// A line of Markdown text, with the marks that would format it or start a block escaped.
+struct MarkdownEscaped<'a>(&'a str);
+
+impl Display for MarkdownEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.0;
+        // `1.` or `1)` would start an ordered list.
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        let ordered = digits > 0 && matches!(line[digits..].chars().next(), Some('.' | ')'));
+        for (i, c) in line.char_indices() {
+            let start = i == 0 && matches!(c, '-' | '+' | '#' | '>' | '=' | '|');
+            if start || (ordered && i == digits) || "\\`*_[]<>&~".contains(c) {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+/// A line of AsciiDoc text, passed through with only `<`, `>` and `&` replaced if it has
+/// anything that AsciiDoc would format.
+struct AsciidocEscaped<'a>(&'a str);
+
+impl Display for AsciidocEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.0;
+        // The lines of a label that would start a list item if they were left alone.
+        let numbered = line.trim_start_matches(|c: char| c.is_ascii_digit()).starts_with('.');
+        let marked = line.starts_with(['-', '.'])
+            || numbered
+            || line.contains(|c| "*_`#^~+[]{}\\".contains(c))
+            || ["--", "...", "->", "=>", "<-", "<=", "(C)", "(R)", "(TM)"]
+                .iter()
+                .any(|mark| line.contains(mark));
+        if !marked {
+            return f.write_str(line);
+        }
+        f.write_str("pass:c[")?;
+        for c in line.chars() {
+            if c == ']' {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// A CSV field, quoted if it has a comma, a quote or a line break.
+struct CsvField<'a>(&'a str);
+
+impl Display for CsvField<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.contains([',', '"', '\n', '\r']) {
+            return f.write_str(self.0);
+        }
+        write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+    }
+}
@@ -0,0 +1,261 @@
+//! HTML pages of trees, made of nested `<details>` elements that open and close without
+//! JavaScript.
+
+use std::fmt::{self, Display, Write};
+
+use crate::{style::web_color, GlyphPalette, Tree, TreeStyle};
+
+// ---------------------------------------------------------
+
+/// What goes into an HTML page besides the nodes.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{HtmlOptions, Tree};
+///
+/// let options = HtmlOptions { expanded_depth: Some(1), ..Default::default() };
+/// let tree = Tree::new("foo").with_leaves([Tree::new("bar").with_leaves(["baz"])]);
+/// let html = tree.to_html(&options);
+/// assert!(html.contains("<details open><summary class=\"node\"><span class=\"label\">foo</span>"));
+/// assert!(html.contains("<details><summary class=\"node\"><span class=\"label\">bar</span>"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Title of the page, the root's label by default.
+    pub title: Option<String>,
+    /// Inline CSS and glyph prefixes that draw the branches like the terminal does, instead of
+    /// plain nested lists.
+    pub css: bool,
+    /// A search box that filters the nodes and opens the matching ones.
+    ///
+    /// It's hidden when JavaScript is off, the rest of the page works without it.
+    pub search: bool,
+    /// Deepest level shown when the page is opened, the root is level 0. Deeper nodes start
+    /// collapsed.
+    pub expanded_depth: Option<usize>,
+}
+
+const STYLE: &str = r#".treeleaf, .treeleaf ul { list-style: none; margin: 0; padding: 0; }
+.treeleaf { font-family: ui-monospace, monospace; line-height: 1.25; }
+.treeleaf .node { display: block; white-space: pre; }
+.treeleaf summary { cursor: pointer; }
+.treeleaf summary::-webkit-details-marker { display: none; }
+.treeleaf details:not([open]) > summary .label::after { content: " …"; opacity: 0.6; }
+.treeleaf .glyphs, .treeleaf .annotation { opacity: 0.6; }
+.treeleaf .match { background: yellow; }
+.treeleaf-search { margin-bottom: 1em; }
+"#;
+
+const SCRIPT: &str = r#"(() => {
+  const search = document.querySelector(".treeleaf-search");
+  const items = document.querySelectorAll(".treeleaf li");
+  const details = [...document.querySelectorAll(".treeleaf details")].map((d) => [d, d.open]);
+  search.hidden = false;
+  search.addEventListener("input", () => {
+    const query = search.value.trim().toLowerCase();
+    for (const item of items) {
+      item.hidden = query !== "" && !item.textContent.toLowerCase().includes(query);
+      const label = item.querySelector(".label");
+      label.classList.toggle("match", query !== "" && label.textContent.toLowerCase().includes(query));
+    }
+    for (const [d, open] of details) d.open = query !== "" || open;
+  });
+})();
+"#;
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Writes the tree as a standalone HTML page of nested `<details>` and `<summary>`
+    /// elements, leaves being plain list items.
+    ///
+    /// With [`HtmlOptions::css`], each line starts with the glyphs of [`RenderOptions::glyphs`]
+    /// and the labels keep their colors, so the page looks like the terminal.
+    ///
+    /// [`RenderOptions::glyphs`]: crate::RenderOptions::glyphs
+    pub fn to_html(&self, options: &HtmlOptions) -> String {
+        let mut out = String::new();
+        // Writing to a `String` only fails if a label's `Display` does.
+        let _ = write_page(&mut out, self, options);
+        out
+    }
+}
+
+fn write_page<D, W>(out: &mut W, tree: &Tree<D>, options: &HtmlOptions) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let root = tree.root.to_string();
+    let title = options.title.as_deref().unwrap_or_else(|| root.lines().next().unwrap_or(""));
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>", Escaped(title))?;
+    if options.css {
+        write!(out, "<style>\n{}</style>\n", STYLE)?;
+    }
+    writeln!(out, "</head>\n<body>")?;
+    if options.search {
+        writeln!(
+            out,
+            "<input type=\"search\" class=\"treeleaf-search\" placeholder=\"Search\" \
+             aria-label=\"Search\" hidden>"
+        )?;
+    }
+
+    writeln!(out, "<ul class=\"treeleaf\">")?;
+    let page = Page { options, glyphs: options.css.then(|| &tree.options().glyphs) };
+    page.write_node(out, tree, "", None, 0)?;
+    writeln!(out, "</ul>")?;
+
+    if options.search {
+        write!(out, "<script>\n{}</script>\n", SCRIPT)?;
+    }
+    writeln!(out, "</body>\n</html>")
+}
+
+struct Page<'a> {
+    options: &'a HtmlOptions,
+    /// The palette of the prefixes, `None` without CSS.
+    glyphs: Option<&'a GlyphPalette>,
+}
+
+impl Page<'_> {
+    /// Writes a node whose ancestors draw `prefix`, `last` being `None` for the root.
+    fn write_node<D, W>(
+        &self,
+        out: &mut W,
+        tree: &Tree<D>,
+        prefix: &str,
+        last: Option<bool>,
+        depth: usize,
+    ) -> fmt::Result
+    where
+        D: Display,
+        W: Write,
+    {
+        let (item, skip) = match (self.glyphs, last) {
+            (Some(glyphs), Some(last)) => {
+                let ((item, item_indent), (skip, skip_indent)) =
+                    (glyphs.item(last), glyphs.skip(last));
+                (
+                    format!("{}{}{}", prefix, item, item_indent),
+                    format!("{}{}{}", prefix, skip, skip_indent),
+                )
+            }
+            _ => (prefix.to_owned(), prefix.to_owned()),
+        };
+
+        let label = Label { tree, item: &item, skip: &skip, glyphs: self.glyphs.is_some() };
+        if tree.leaves.is_empty() {
+            return writeln!(out, "<li><span class=\"node\">{}</span></li>", label);
+        }
+
+        let collapsed = matches!(self.options.expanded_depth, Some(max) if depth >= max);
+        let open = if collapsed { "" } else { " open" };
+        writeln!(out, "<li><details{}><summary class=\"node\">{}</summary>", open, label)?;
+        writeln!(out, "<ul>")?;
+        for (i, leaf) in tree.leaves.iter().enumerate() {
+            let last = i + 1 == tree.leaves.len();
+            self.write_node(out, leaf, &skip, Some(last), depth + 1)?;
+        }
+        writeln!(out, "</ul>\n</details></li>")
+    }
+}
+
+/// The glyphs, label and annotation of a node.
+struct Label<'a, D>
+where
+    D: Display,
+{
+    tree: &'a Tree<D>,
+    /// Glyphs in front of the first line.
+    item: &'a str,
+    /// Glyphs in front of the continuation lines.
+    skip: &'a str,
+    glyphs: bool,
+}
+
+impl<D> Display for Label<'_, D>
+where
+    D: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = self.tree.root.to_string();
+        let lines: Vec<&str> = match self.tree.multiline.splits(&label) {
+            true => label.lines().collect(),
+            false => vec![label.as_str()],
+        };
+
+        if self.glyphs && !self.item.is_empty() {
+            write!(f, "<span class=\"glyphs\">{}</span>", Escaped(self.item))?;
+        }
+        f.write_str("<span class=\"label\"")?;
+        if let Some(style) = &self.tree.style {
+            write!(f, " style=\"{}\"", Css(style))?;
+        }
+        f.write_str(">")?;
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str("<br>")?;
+                if self.glyphs && !self.skip.is_empty() {
+                    write!(f, "<span class=\"glyphs\">{}</span>", Escaped(self.skip))?;
+                }
+            }
+            write!(f, "{}", Escaped(line))?;
+        }
+        f.write_str("</span>")?;
+
+        if let Some(annotation) = &self.tree.annotation {
+            write!(f, "  <span class=\"annotation\">{}</span>", Escaped(annotation))?;
+        }
+        Ok(())
+    }
+}
+
+/// The declarations of a `style` attribute.
+struct Css<'a>(&'a TreeStyle);
+
+impl Display for Css<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = self.0;
+        let mut declarations = Vec::new();
+        if let Some(color) = style.foreground().and_then(web_color) {
+            declarations.push(format!("color: {}", color));
+        }
+        if let Some(color) = style.background().and_then(web_color) {
+            declarations.push(format!("background-color: {}", color));
+        }
+        if style.has_attribute("bold") {
+            declarations.push("font-weight: bold".to_owned());
+        }
+        if style.has_attribute("dim") {
+            declarations.push("opacity: 0.6".to_owned());
+        }
+        if style.has_attribute("underlined") {
+            declarations.push("text-decoration: underline".to_owned());
+        }
+        f.write_str(&declarations.join("; "))
+    }
+}
+
+/// Text inside an element or a double-quoted attribute.
+struct Escaped<'a>(&'a str);
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,177 +1,46 @@
 mod binary_tree;
+mod document;
+mod dot;
+mod export;
+mod html;
+mod mermaid;
+mod mindmap;
+mod parse;
+mod render;
+mod style;
+mod svg;
 mod term_tree;
 #[cfg(test)]
 mod tests;
+mod transform;
+mod traverse;
+pub mod treeroot;
 
 // ---------------------------------------------------------
 
 #[macro_use]
 extern crate lazy_static;
 
-use std::{error::Error, fs::File, path::Path, thread, time::Duration};
+use std::{error::Error, fs::File, path::Path};
 
 use console::Style;
 use dialoguer::{theme::ColorfulTheme, Confirm, Input};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use crate::{binary_tree::BinaryTree, term_tree::*};
+pub use crate::{
+    binary_tree::BinaryTree, document::*, dot::*, export::*, html::*, mermaid::*, mindmap::*,
+    parse::*, style::*, svg::*, term_tree::*, traverse::*,
+};
 
 // ---------------------------------------------------------
 
 lazy_static! {
     static ref THEME: ColorfulTheme = ColorfulTheme::default();
-    static ref CONFIG_FILE_PATH: &'static str = "config.json";
 }
 
 // ---------------------------------------------------------
 
-pub fn tree<P>(p: P) -> std::io::Result<Tree<String>>
-where
-    P: AsRef<Path> + std::fmt::Debug,
-{
-    println!("Path to file: {:?}", p);
-
-    // render tree with leaves
-    let tree = Tree::new("foo".to_string()).with_leaves(
-        // new nodes
-        [Tree::new("bar".to_string()).with_leaves(
-            // new nodes
-            ["baz".to_string()],
-        )],
-    );
-    println!("{}", format!("{}", tree));
-    // render tree with multiple leaves
-    let tree = Tree::new("foo".to_string()).with_leaves(
-        // new nodes
-        ["bar".to_string(), "baz".to_string()],
-    );
-    println!("{}", format!("{}", tree));
-    // render tree with multiline leaves
-    let tree = Tree::new("foo".to_string()).with_leaves([
-        // new nodes
-        Tree::new("bar\nbaz".to_string()).with_multiline(Multiline::True),
-        Tree::new("foo\nbar".to_string()).with_multiline(Multiline::True),
-    ]);
-    println!("{}", format!("{}", tree));
-
-    Ok(tree)
-}
-
-// ---------------------------------------------------------
-
-pub fn run() -> Result<(), Box<dyn Error>> {
-    {
-        treeroot::run();
-        thread::sleep(Duration::from_millis(10000));
-    }
-    {
-        let path = Path::new("tree.json"); // cli.path OR DialogueConfig.path
-        let tree_str = tree(path)?;
-        let _tree_str = format!("{}", tree_str);
-        thread::sleep(Duration::from_millis(10000));
-    }
-
-    let dialogue_cfg: Option<DialogueConfig> =
-        match DialogueConfig::default().init_dialogue_config() {
-            Ok(None) => {
-                println!("Aborted.");
-                None
-            }
-            Ok(Some(config)) => {
-                println!("{:?}", config);
-                Some(config)
-            }
-            Err(err) => {
-                println!("error: {}", err);
-                None
-            }
-        };
-
-    let dialogue_cfg = if let Some(x) = dialogue_cfg { x } else { DialogueConfig::default() };
-
-    let vec_nodes = dialogue_cfg.rest_nodes.unwrap();
-    let mut rest = vec_nodes.iter().map(|x| x.node.as_str()).collect::<Vec<_>>();
-    let root = dialogue_cfg.root_node.node.as_str();
-    rest.insert(0, root);
-    let vec = rest.clone();
-    // let tree = BinaryTree::new(&root);
-    let tree = BinaryTree::from_vec(&vec);
-    println!("{:#?}", &tree);
-    {
-        let path = "tree.json";
-        let mut file = File::create(path)?;
-        serde_json::to_writer(&mut file, &tree)?;
-    }
-
-    //TODO: A Parse binary tree to mermaid-diagram.
-    //TODO: B draft::playground(tree)?;
-    Ok(())
-}
-
-// ---------------------------------------------------------
-
-mod treeroot {
-
-    use std::{
-        fs::{self, DirEntry, Metadata},
-        path::Path,
-    };
-
-    use crate::Tree;
-
-    /// Returns the final component of the `Path`, if there is one.
-    ///
-    /// If the path is a normal file, this is the file name. If it's the path of a directory, this
-    /// is the directory name.
-    ///
-    /// Returns [`None`] if the path terminates in `..`.
-    fn label<P>(path: P) -> String
-    where
-        P: AsRef<Path>,
-    {
-        path.as_ref().file_name().unwrap().to_str().unwrap().to_owned()
-    }
-
-    // * `fold()` - fn fold<Acc, Fold>(self, init: Acc, fold: Fold) -> Acc
-    // * `canonicalize` - Returns the canonical, absolute form of the path with all intermediate
-    //   components normalized and symbolic links resolved.
-    // * `metadata` - Returns the metadata for the file that this entry points at.
-    fn tree<P>(path: P) -> std::io::Result<Tree<String>>
-    where
-        P: AsRef<Path>,
-    {
-        let result = fs::read_dir(&path)?.filter_map(|e| e.ok()).fold(
-            Tree::new(label(path.as_ref().canonicalize()?)),
-            |mut root: Tree<String>, entry: DirEntry| {
-                let dir: Metadata = entry.metadata().unwrap();
-                if dir.is_dir() {
-                    root.push(tree(entry.path()).unwrap());
-                } else {
-                    root.push(Tree::new(label(entry.path())));
-                }
-                root
-            },
-        );
-        Ok(result)
-    }
-
-    pub fn run() {
-        let dir: String = std::env::args().nth(1).unwrap_or_else(|| String::from("."));
-        match tree(dir) {
-            Ok(tree) => println!("{}", tree),
-            Err(e) => eprintln!("error: {}", e),
-        }
-    }
-}
-
-// ---------------------------------------------------------
-
-enum Nodes {
-    Root,
-    Child,
-}
-
 // #[derive(Debug, Serialize, Deserialize)]
 // struct DialogueNode(u32, String, String);
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -226,13 +95,15 @@ impl DialogueConfig {
             child: side.to_string(),
             uuid: ChildPatUuid { child: side, pat: Some('-'), uuid },
         };
-        let left_or_right = match i % 2 != 0 {
-            true => which_child("left"),
-            false => which_child("right"),
-        };
-        left_or_right
+        match i % 2 {
+            0 => which_child("right"),
+            _ => which_child("left"),
+        }
     }
 
+    /// Prompts for the root and child nodes on the terminal.
+    ///
+    /// Returns `None` if the user declines to save the answers.
     pub fn init_dialogue_config(mut self) -> Result<Option<DialogueConfig>, Box<dyn Error>> {
         const ROOT_IDX: u32 = 0;
         let theme = Self::theme();
@@ -281,8 +152,6 @@ impl DialogueConfig {
         self.root_node = DialogueNode { index: ROOT_IDX, uuid: uuid.to_string(), node: root_node };
         self.rest_nodes = Some(children);
 
-        self.print_to_config_file(*CONFIG_FILE_PATH)?;
-
         Ok(Some(self))
     }
 
@@ -295,24 +164,48 @@ impl DialogueConfig {
         }
     }
 
-    /// .
+    /// Reads a config previously saved with [`DialogueConfig::print_to_config_file`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be opened or isn't a valid config.
+    pub fn from_config_file<P>(path: P) -> Result<Self, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Builds a balanced [`BinaryTree`] from the root node followed by the rest of the nodes.
+    pub fn to_binary_tree(&self) -> BinaryTree<&str> {
+        let rest = self.rest_nodes.iter().flatten().map(|x| x.node.as_str());
+        let nodes: Vec<&str> = std::iter::once(self.root_node.node.as_str()).chain(rest).collect();
+        BinaryTree::from_vec(&nodes)
+    }
+
+    /// Formats the default answer for a node prompt, e.g. `left-22e40956`.
     ///
     /// # Errors
     ///
-    /// This function will return an error if .
-    fn parse_default_node<'a>(cpu: ChildPatUuid<'a>) -> Result<String, Box<dyn Error>> {
-        let pat = if let Some(p) = cpu.pat { p } else { '-' };
+    /// This function currently never returns an error.
+    fn parse_default_node(cpu: ChildPatUuid<'_>) -> Result<String, Box<dyn Error>> {
+        let pat = cpu.pat.unwrap_or('-');
 
         let uuid = cpu.uuid.to_string();
-        let uuid =
-            if let Some(it) = uuid.split('-').collect::<Vec<_>>().first() { it } else { cpu.child };
+        let uuid = uuid.split('-').next().unwrap_or(cpu.child);
         Ok(format!("{}{}{}", cpu.child, pat, uuid))
     }
 
+    /// Saves the config as JSON to `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be created or written to.
     //PERF: Use tempfile?
-    fn print_to_config_file<P>(&self, path: P) -> Result<(), Box<dyn Error>>
+    pub fn print_to_config_file<P>(&self, path: P) -> Result<(), Box<dyn Error>>
     where
-        P: AsRef<std::path::Path>,
+        P: AsRef<Path>,
     {
         let mut file = File::create(path)?;
         serde_json::to_writer(&mut file, &self)?;
@@ -326,7 +219,8 @@ impl DialogueConfig {
 
 // ---------------------------------------------------------
 
-#[allow(unused)]
+//TODO: B draft::playground(tree)?;
+#[allow(unused, clippy::dbg_macro, clippy::exit)]
 mod draft {
     use std::error::Error;
 
@@ -1,11 +1,510 @@
-use treeleaf::run;
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-fn main() {
-    match run() {
-        Ok(t) => t,
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Args, Parser, Subcommand, ValueEnum,
+};
+use strum::VariantNames;
+use treeleaf::{
+    treeroot, BinaryTree, ColorChoice, DialogueConfig, Direction, DotOptions, GlyphPalette,
+    GlyphPreset, HtmlOptions, JsonOptions, LineWidth, MarkupStyle, MermaidOptions, SvgOptions,
+    Tree, TreeStyle,
+};
+
+/// Exit code when reading, parsing or writing a tree fails.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code when the wizard is interrupted with `Ctrl-C`.
+const EXIT_INTERRUPTED: i32 = 130;
+
+// ---------------------------------------------------------
+
+/// Tree diagrams for the terminal.
+#[derive(Debug, Parser)]
+#[command(name = "treeleaf", version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Render a directory like `tree(1)`
+    Dir {
+        /// Directory to walk
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Print the size of each entry, and the total size of each directory
+        #[arg(short, long)]
+        sizes: bool,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// Render a tree file, by default binary tree JSON such as the one saved by `wizard`
+    Render {
+        /// File to read, or `-` for stdin
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Format of the file
+        #[arg(long, value_enum, default_value_t = InputFormat::Binary)]
+        from: InputFormat,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// Explore a JSON document, such as an API payload
+    Json {
+        /// File to read, or `-` for stdin
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Show the type of each value
+        #[arg(short, long)]
+        types: bool,
+        /// Cut strings longer than this many characters
+        #[arg(long, value_name = "CHARS")]
+        max_string: Option<usize>,
+        /// Show this many items of each array
+        #[arg(long, value_name = "COUNT")]
+        max_items: Option<usize>,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// Create a binary tree interactively and save it
+    Wizard {
+        /// Where to save the wizard answers
+        #[arg(long, default_value = "config.json")]
+        config: PathBuf,
+        /// Where to save the binary tree JSON
+        #[arg(short, long, default_value = "tree.json")]
+        output: PathBuf,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// Convert saved wizard answers into a binary tree JSON file
+    Convert {
+        /// Wizard config file to read
+        #[arg(default_value = "config.json")]
+        config: PathBuf,
+        /// File to write, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export a binary tree JSON file to another format
+    Export {
+        /// File to read, or `-` for stdin
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Direction of Mermaid flowcharts and DOT graphs
+        #[arg(
+            long,
+            default_value_t = Direction::default(),
+            value_parser = PossibleValuesParser::new(Direction::VARIANTS)
+                .map(|s| s.parse::<Direction>().unwrap()),
+        )]
+        direction: Direction,
+        /// File to write, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+}
+
+/// Options for commands that draw a tree.
+#[derive(Debug, Args)]
+struct RenderArgs {
+    /// Glyphs used to draw the branches, `box` unless the tree has its own
+    #[arg(
+        short,
+        long,
+        value_parser = PossibleValuesParser::new(GlyphPreset::VARIANTS)
+            .map(|s| s.parse::<GlyphPreset>().unwrap()),
+    )]
+    glyphs: Option<GlyphPreset>,
+    /// JSON theme file with custom glyphs, instead of a preset
+    #[arg(long, conflicts_with = "glyphs")]
+    theme: Option<PathBuf>,
+    /// Columns per level of indentation
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=16))]
+    indent: Option<u8>,
+    /// When to use colors, `auto` unless the tree says otherwise
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(ColorChoice::VARIANTS)
+            .map(|s| s.parse::<ColorChoice>().unwrap()),
+    )]
+    color: Option<ColorChoice>,
+    /// Styles for the branches, cycled by depth, e.g. `red,yellow,green.bold`
+    #[arg(long, value_delimiter = ',')]
+    depth_styles: Vec<TreeStyle>,
+    /// Deepest level to show, the root is level 0
+    #[arg(short = 'L', long)]
+    max_depth: Option<usize>,
+    /// Most children to show per node
+    #[arg(long)]
+    max_children: Option<usize>,
+    /// Wrap labels to `--wrap=COLUMNS`, or to the terminal with `auto` or no value
+    #[arg(
+        short,
+        long,
+        value_name = "COLUMNS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto"
+    )]
+    wrap: Option<LineWidth>,
+    /// Cut lines to this many columns with `…`, or to the terminal with `auto`
+    #[arg(long, value_name = "COLUMNS")]
+    truncate: Option<LineWidth>,
+    /// Also draw the tree as an SVG image to this file, within `--max-depth` and `--max-children`
+    #[arg(long, value_name = "FILE")]
+    export_svg: Option<PathBuf>,
+    /// Also write the tree as Markdown to this file
+    #[arg(long, value_name = "FILE")]
+    export_markdown: Option<PathBuf>,
+    /// Also write the tree as AsciiDoc to this file
+    #[arg(long, value_name = "FILE")]
+    export_asciidoc: Option<PathBuf>,
+    /// Also write one CSV row per node to this file
+    #[arg(long, value_name = "FILE")]
+    export_csv: Option<PathBuf>,
+    /// Also write the tree and its options as JSON to this file, readable with `--from json`
+    #[arg(long, value_name = "FILE")]
+    export_json: Option<PathBuf>,
+    /// Whether Markdown and AsciiDoc are nested lists or the rendering in a code block
+    #[arg(
+        long,
+        default_value_t = MarkupStyle::default(),
+        value_parser = PossibleValuesParser::new(MarkupStyle::VARIANTS)
+            .map(|s| s.parse::<MarkupStyle>().unwrap()),
+    )]
+    markup: MarkupStyle,
+}
+
+impl RenderArgs {
+    /// The palette picked with `--glyphs` or `--theme`, if any.
+    fn palette(&self) -> Result<Option<GlyphPalette>, Box<dyn Error>> {
+        Ok(match &self.theme {
+            Some(path) => {
+                let theme = read_input(path)
+                    .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+                let glyphs = GlyphPalette::from_theme(&theme)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                Some(glyphs)
+            }
+            None => self.glyphs.map(GlyphPalette::from),
+        })
+    }
+
+    /// Streams `tree` to the file at `path`, or to stdout if there is none.
+    fn render<D>(&self, mut tree: Tree<D>, path: Option<&Path>) -> Result<(), Box<dyn Error>>
+    where
+        D: Display,
+    {
+        self.apply(&mut tree)?;
+        // Exported JSON holds the labels as text, which is what `--from json` reads back.
+        let mut tree = tree.map(|root| root.to_string());
+        self.export(&tree)?;
+        // `auto` only colors a terminal, and a file isn't one whatever stdout is.
+        if path.is_some() && tree.options().color == ColorChoice::Auto {
+            tree.options_mut().color = ColorChoice::Never;
+        }
+        let mut out = output(path)?;
+        tree.write_to(&mut out)?;
+        Ok(out.flush()?)
+    }
+
+    /// Writes the files asked for with the `--export-*` options.
+    fn export(&self, tree: &Tree<String>) -> Result<(), Box<dyn Error>> {
+        if let Some(svg) = &self.export_svg {
+            let options = tree.options();
+            let options = SvgOptions {
+                max_depth: options.max_depth,
+                max_children: options.max_children,
+                ..Default::default()
+            };
+            write_output(Some(svg), &tree.to_svg(&options))?;
+        }
+        if let Some(markdown) = &self.export_markdown {
+            write_output(Some(markdown), &tree.to_markdown(self.markup))?;
+        }
+        if let Some(asciidoc) = &self.export_asciidoc {
+            write_output(Some(asciidoc), &tree.to_asciidoc(self.markup))?;
+        }
+        if let Some(csv) = &self.export_csv {
+            write_output(Some(csv), &tree.to_csv())?;
+        }
+        if let Some(path) = &self.export_json {
+            let json = serde_json::to_string_pretty(tree)?;
+            write_output(Some(path), &format!("{}\n", json))?;
+        }
+        Ok(())
+    }
+
+    /// How to draw a binary tree, which has no options of its own, as an SVG image.
+    fn svg_options(&self) -> SvgOptions {
+        SvgOptions {
+            max_depth: self.max_depth,
+            max_children: self.max_children,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the options of `tree`, overriding the ones it already has.
+    fn apply<D>(&self, tree: &mut Tree<D>) -> Result<(), Box<dyn Error>>
+    where
+        D: Display,
+    {
+        let palette = self.palette()?;
+        let options = tree.options_mut();
+        if let Some(glyphs) = palette {
+            options.glyphs = glyphs;
+        }
+        if let Some(width) = self.indent {
+            options.glyphs = options.glyphs.clone().with_indent(width.into());
+        }
+        options.color = self.color.unwrap_or(options.color);
+        if !self.depth_styles.is_empty() {
+            options.depth_styles = self.depth_styles.clone();
+        }
+        options.max_depth = self.max_depth.or(options.max_depth);
+        options.max_children = self.max_children.or(options.max_children);
+        options.wrap = self.wrap.or(options.wrap);
+        options.truncate = self.truncate.or(options.truncate);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// Binary tree JSON
+    Binary,
+    /// A Mermaid flowchart, or the first one in a Markdown file
+    Mermaid,
+    /// A Mermaid mindmap, or the first one in a Markdown file
+    Mindmap,
+    /// A tree serialized as JSON, with its rendering options
+    Json,
+    /// Any YAML document, such as a config file
+    Yaml,
+    /// Any TOML document, such as a config file
+    Toml,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The terminal rendering
+    Text,
+    /// Pretty-printed JSON
+    Json,
+    /// A Mermaid flowchart
+    Mermaid,
+    /// A Mermaid mindmap
+    Mindmap,
+    /// A Graphviz DOT graph
+    Dot,
+    /// An HTML page with collapsible nodes and a search box, `--max-depth` collapses nodes
+    Html,
+    /// An SVG image, within `--max-depth` and `--max-children`
+    Svg,
+    /// Markdown, styled with `--markup`
+    Markdown,
+    /// AsciiDoc, styled with `--markup`
+    Asciidoc,
+    /// One CSV row per node
+    Csv,
+}
+
+// ---------------------------------------------------------
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if is_broken_pipe(e.as_ref()) => ExitCode::SUCCESS,
         Err(e) => {
-            eprintln!("called `Result::unwrap()` on an `Err` value: {}", &e);
-            std::process::exit(1);
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Dir { path, sizes, render } => {
+            let tree = match sizes {
+                true => treeroot::tree_with_sizes(&path),
+                false => treeroot::tree(&path),
+            };
+            let tree = tree.map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+            render.render(tree, None)?;
+        }
+        Command::Render { input, from, render } => match from {
+            InputFormat::Binary => render.render(read_binary_tree(&input)?.to_tree(), None)?,
+            InputFormat::Mermaid => {
+                let tree = Tree::from_mermaid(&read_input(&input)?)
+                    .map_err(|e| format!("{}: {}", input.display(), e))?;
+                render.render(tree, None)?;
+            }
+            InputFormat::Mindmap => {
+                let tree = Tree::from_mindmap(&read_input(&input)?)
+                    .map_err(|e| format!("{}: {}", input.display(), e))?;
+                render.render(tree, None)?;
+            }
+            InputFormat::Json => render.render(read_tree(&input)?, None)?,
+            InputFormat::Yaml | InputFormat::Toml => {
+                let text = read_input(&input)?;
+                let tree = match from {
+                    InputFormat::Yaml => Tree::from_yaml(&text),
+                    _ => Tree::from_toml(&text),
+                };
+                let mut tree = tree.map_err(|e| format!("{}: {}", input.display(), e))?;
+                if input != Path::new("-") && tree.root == "." {
+                    tree.root = input.display().to_string();
+                }
+                render.render(tree, None)?;
+            }
+        },
+        Command::Json { input, types, max_string, max_items, render } => {
+            let json = read_input(&input)
+                .map_err(|e| format!("failed to read {}: {}", input.display(), e))?;
+            let value: serde_json::Value = serde_json::from_str(&json)
+                .map_err(|e| format!("{} is not JSON: {}", input.display(), e))?;
+            let options = JsonOptions { types, max_string, max_items };
+            let mut tree = Tree::from_json(&value, &options);
+            // The root is `.`, followed by its type with `--types`.
+            if input != Path::new("-") && tree.root.starts_with('.') {
+                tree.root.replace_range(..1, &input.display().to_string());
+            }
+            render.render(tree, None)?;
+        }
+        Command::Wizard { config, output, mut render } => {
+            // Restore the cursor hidden by the prompts before bailing out.
+            #[allow(clippy::exit)]
+            ctrlc::set_handler(|| {
+                let _ = console::Term::stderr().show_cursor();
+                std::process::exit(EXIT_INTERRUPTED);
+            })?;
+            let answers = DialogueConfig::default()
+                .init_dialogue_config()?
+                .ok_or("aborted, nothing was saved")?;
+            answers.print_to_config_file(&config)?;
+
+            let tree = answers.to_binary_tree();
+            write_output(Some(&output), &serde_json::to_string(&tree)?)?;
+            // Drawn as a binary tree, so that an only child leans towards its side.
+            if let Some(svg) = render.export_svg.take() {
+                write_output(Some(&svg), &tree.to_svg(&render.svg_options()))?;
+            }
+            render.render(tree.to_tree(), None)?;
+        }
+        Command::Convert { config, output } => {
+            let answers = DialogueConfig::from_config_file(&config)
+                .map_err(|e| format!("failed to read {}: {}", config.display(), e))?;
+            let json = serde_json::to_string_pretty(&answers.to_binary_tree())?;
+            write_output(output.as_deref(), &format!("{}\n", json))?;
+        }
+        Command::Export { input, format, direction, output, render } => {
+            let tree = read_binary_tree(&input)?;
+            match format {
+                Format::Text => render.render(tree.to_tree(), output.as_deref())?,
+                Format::Json => {
+                    let json = serde_json::to_string_pretty(&tree)?;
+                    write_output(output.as_deref(), &format!("{}\n", json))?;
+                }
+                Format::Mermaid => {
+                    let options = MermaidOptions { direction, ..Default::default() };
+                    write_output(output.as_deref(), &tree.to_mermaid(&options))?;
+                }
+                Format::Mindmap => write_output(output.as_deref(), &tree.to_tree().to_mindmap())?,
+                Format::Dot => {
+                    let options = DotOptions { direction, ..Default::default() };
+                    write_output(output.as_deref(), &tree.to_dot(&options))?;
+                }
+                Format::Html => {
+                    let mut tree = tree.to_tree();
+                    render.apply(&mut tree)?;
+                    let options = HtmlOptions {
+                        css: true,
+                        search: true,
+                        expanded_depth: tree.options().max_depth,
+                        ..Default::default()
+                    };
+                    write_output(output.as_deref(), &tree.to_html(&options))?;
+                }
+                Format::Svg => {
+                    write_output(output.as_deref(), &tree.to_svg(&render.svg_options()))?;
+                }
+                Format::Markdown | Format::Asciidoc | Format::Csv => {
+                    let mut tree = tree.to_tree();
+                    render.apply(&mut tree)?;
+                    let text = match format {
+                        Format::Markdown => tree.to_markdown(render.markup),
+                        Format::Asciidoc => tree.to_asciidoc(render.markup),
+                        _ => tree.to_csv(),
+                    };
+                    write_output(output.as_deref(), &text)?;
+                }
+            }
         }
     }
+    Ok(())
+}
+
+// ---------------------------------------------------------
+
+/// Reads `path` to a string, treating `-` as stdin.
+fn read_input(path: &Path) -> io::Result<String> {
+    let mut buf = String::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_string(&mut buf)?;
+    } else {
+        buf = fs::read_to_string(path)?;
+    }
+    Ok(buf)
+}
+
+fn read_binary_tree(path: &Path) -> Result<BinaryTree<String>, Box<dyn Error>> {
+    let input =
+        read_input(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let tree = serde_json::from_str(&input)
+        .map_err(|e| format!("{} is not a binary tree: {}", path.display(), e))?;
+    Ok(tree)
+}
+
+fn read_tree(path: &Path) -> Result<Tree<String>, Box<dyn Error>> {
+    let input =
+        read_input(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let tree = serde_json::from_str(&input)
+        .map_err(|e| format!("{} is not a tree: {}", path.display(), e))?;
+    Ok(tree)
+}
+
+/// Opens the file at `path` for writing, or stdout if there is none.
+fn output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+/// Writes `contents` to the file at `path`, or to stdout if there is none.
+fn write_output(path: Option<&Path>, contents: &str) -> io::Result<()> {
+    let mut out = output(path)?;
+    out.write_all(contents.as_bytes())?;
+    out.flush()
+}
+
+/// Output piped into e.g. `head` shouldn't be reported as a failure.
+fn is_broken_pipe(e: &(dyn Error + 'static)) -> bool {
+    matches!(e.downcast_ref::<io::Error>(), Some(e) if e.kind() == io::ErrorKind::BrokenPipe)
 }
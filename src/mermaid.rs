@@ -0,0 +1,487 @@
+//! [Mermaid](https://mermaid.js.org/syntax/flowchart.html) flowcharts of trees.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Write},
+};
+
+use regex::Regex;
+use snafu::{ensure, OptionExt, Snafu};
+use strum::{Display as StrumDisplay, EnumString, EnumVariantNames};
+
+use crate::{BinaryTree, Tree};
+
+// ---------------------------------------------------------
+
+/// How a flowchart starts and which way it grows.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{Direction, MermaidOptions, Tree};
+///
+/// let options = MermaidOptions { direction: Direction::LeftRight, ..Default::default() };
+/// let tree = Tree::new("foo").with_leaves(["bar"]);
+/// assert_eq!(tree.to_mermaid(&options), "graph LR\n    n[\"foo\"]\n    n --> n_0[\"bar\"]\n");
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct MermaidOptions {
+    pub keyword: MermaidKeyword,
+    pub direction: Direction,
+}
+
+/// The keyword a flowchart is declared with, both are understood by every Mermaid version that
+/// has `flowchart`.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, EnumString, EnumVariantNames, StrumDisplay,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum MermaidKeyword {
+    #[default]
+    Graph,
+    Flowchart,
+}
+
+/// Direction of a flowchart, parsed from and displayed as its Mermaid spelling such as `TD`.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, EnumString, EnumVariantNames, StrumDisplay,
+)]
+pub enum Direction {
+    #[default]
+    #[strum(to_string = "TD", serialize = "TB")]
+    TopDown,
+    #[strum(serialize = "BT")]
+    BottomUp,
+    #[strum(serialize = "LR")]
+    LeftRight,
+    #[strum(serialize = "RL")]
+    RightLeft,
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Draws the tree as a Mermaid flowchart.
+    ///
+    /// Node IDs follow the path from the root: the root is `n` and the second child of its first
+    /// child is `n_0_1`, so they don't change between runs.
+    pub fn to_mermaid(&self, options: &MermaidOptions) -> String {
+        let mut out = format!("{} {}\n", options.keyword, options.direction);
+        // Writing to a `String` only fails if a label's `Display` does.
+        let _ = writeln!(out, "    n[\"{}\"]", Escaped(&self.root))
+            .and_then(|()| write_leaves(&mut out, self, "n"));
+        out
+    }
+}
+
+fn write_leaves<D, W>(out: &mut W, tree: &Tree<D>, id: &str) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    for (i, leaf) in tree.leaves.iter().enumerate() {
+        let child = format!("{}_{}", id, i);
+        writeln!(out, "    {} --> {}[\"{}\"]", id, child, Escaped(&leaf.root))?;
+        write_leaves(out, leaf, &child)?;
+    }
+    Ok(())
+}
+
+impl<T> BinaryTree<T>
+where
+    T: Display,
+{
+    /// Draws the tree as a Mermaid flowchart.
+    ///
+    /// Node IDs follow the path from the root: the root is `n` and the right child of its left
+    /// child is `nLR`, so they don't change between runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{BinaryTree, MermaidOptions};
+    ///
+    /// let tree = BinaryTree::new(1).with_left(BinaryTree::new(2)).with_right(BinaryTree::new(3));
+    /// assert_eq!(
+    ///     tree.to_mermaid(&MermaidOptions::default()),
+    ///     "graph TD\n    n[\"1\"]\n    n --> nL[\"2\"]\n    n --> nR[\"3\"]\n"
+    /// );
+    /// ```
+    pub fn to_mermaid(&self, options: &MermaidOptions) -> String {
+        let mut out = format!("{} {}\n", options.keyword, options.direction);
+        let _ = writeln!(out, "    n[\"{}\"]", Escaped(&self.value))
+            .and_then(|()| write_children(&mut out, self, "n"));
+        out
+    }
+}
+
+fn write_children<T, W>(out: &mut W, tree: &BinaryTree<T>, id: &str) -> fmt::Result
+where
+    T: Display,
+    W: Write,
+{
+    for (side, node) in [("L", &tree.left), ("R", &tree.right)] {
+        if let Some(node) = node {
+            let child = format!("{}{}", id, side);
+            writeln!(out, "    {} --> {}[\"{}\"]", id, child, Escaped(&node.value))?;
+            write_children(out, node, &child)?;
+        }
+    }
+    Ok(())
+}
+
+/// A label inside the double quotes of a Mermaid node.
+///
+/// Quotes, `#` and angle brackets become entity codes and newlines become `<br>`.
+pub(crate) struct Escaped<'a, D>(pub(crate) &'a D);
+
+impl<D> Display for Escaped<'_, D>
+where
+    D: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.to_string().chars() {
+            match c {
+                '#' => f.write_str("#35;")?,
+                '"' => f.write_str("#quot;")?,
+                '<' => f.write_str("#lt;")?,
+                '>' => f.write_str("#gt;")?,
+                '\n' => f.write_str("<br>")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum MermaidError {
+    #[snafu(display("there are no nodes to read"))]
+    Empty,
+    #[snafu(display("line {line}: can't read `{statement}`"))]
+    Syntax { line: usize, statement: String },
+    #[snafu(display("line {line}: `{node}` already has a parent, the flowchart isn't a tree"))]
+    SecondParent { line: usize, node: String },
+    #[snafu(display("line {line}: `{node}` links back to one of its ancestors"))]
+    Cycle { line: usize, node: String },
+    #[snafu(display(
+        "line {line}: `{node}` is a second node without a parent, `{root}` is the first"
+    ))]
+    SecondRoot { line: usize, node: String, root: String },
+}
+
+impl Tree<String> {
+    /// Reads a Mermaid flowchart such as `graph TD`, or the first flowchart in the `mermaid` code
+    /// blocks of a Markdown document.
+    ///
+    /// Nodes are labeled with the text of their first shape, like `1` in `R(1)`, or their ID.
+    /// Children are ordered like their links, and link labels, styles and subgraphs are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let markdown = "A tree:\n\n```mermaid\nR(1)\nR-->L1(2)\nR-->R1(3)\nL1-->L2(4)\n```\n";
+    /// let tree = Tree::from_mermaid(markdown).unwrap();
+    /// assert_eq!(tree.to_string(), "1\n├── 2\n│   └── 4\n└── 3\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the offending line if a statement can't be read,
+    /// or if the links don't form a single tree.
+    pub fn from_mermaid(text: &str) -> Result<Self, MermaidError> {
+        let (offset, block) = mermaid_block(text, |keyword| !OTHER_DIAGRAMS.contains(&keyword));
+        let mut flowchart = Flowchart::default();
+
+        for (number, line) in (offset + 1..).zip(block.lines()) {
+            let line = line.split("%%").next().unwrap_or_default();
+            for statement in split_statements(line) {
+                flowchart.statement(number, statement)?;
+            }
+        }
+        flowchart.finish()
+    }
+}
+
+/// Diagrams that can't be read as flowcharts, by their first keyword.
+const OTHER_DIAGRAMS: [&str; 12] = [
+    "mindmap",
+    "sequenceDiagram",
+    "classDiagram",
+    "stateDiagram",
+    "stateDiagram-v2",
+    "erDiagram",
+    "journey",
+    "gantt",
+    "pie",
+    "gitGraph",
+    "timeline",
+    "quadrantChart",
+];
+
+/// The line offset and contents of the first `mermaid` code block whose first keyword is
+/// accepted by `is_kind`, or the whole text if there are no `mermaid` code blocks at all.
+pub(crate) fn mermaid_block(text: &str, is_kind: fn(&str) -> bool) -> (usize, &str) {
+    let mut position = 0;
+    let mut fenced = false;
+    // The line offset and start of the block, once its fence has been seen.
+    let mut block: Option<(usize, usize)> = None;
+    for (number, line) in text.split_inclusive('\n').enumerate() {
+        let fence = line.trim_start();
+        let is_fence = fence.starts_with("```") || fence.starts_with("~~~");
+        match block {
+            None if is_fence && fence[3..].trim_start().starts_with("mermaid") => {
+                block = Some((number + 1, position + line.len()));
+                fenced = true;
+            }
+            Some((offset, start)) if is_fence => {
+                let contents = &text[start..position];
+                if is_kind(first_keyword(contents)) {
+                    return (offset, contents);
+                }
+                block = None;
+            }
+            _ => {}
+        }
+        position += line.len();
+    }
+    match block {
+        Some((offset, start)) if is_kind(first_keyword(&text[start..])) => (offset, &text[start..]),
+        _ if fenced => (0, ""),
+        _ => (0, text),
+    }
+}
+
+/// The first word of a diagram, skipping blank lines and `%%` comments.
+pub(crate) fn first_keyword(block: &str) -> &str {
+    block
+        .lines()
+        .map(|line| line.split("%%").next().unwrap_or_default().trim())
+        .find(|line| !line.is_empty())
+        .and_then(|line| line.split_whitespace().next())
+        .unwrap_or_default()
+}
+
+/// Splits a line at the `;` between statements, except within quotes.
+fn split_statements(line: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                statements.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&line[start..]);
+    statements.into_iter().map(str::trim).filter(|s| !s.is_empty()).collect()
+}
+
+/// Shapes as `(open, close)`, longest first so that `((` wins over `(`.
+const SHAPES: [(&str, &str); 15] = [
+    ("(((", ")))"),
+    ("((", "))"),
+    ("([", "])"),
+    ("[[", "]]"),
+    ("[(", ")]"),
+    ("{{", "}}"),
+    ("[/", "/]"),
+    ("[/", "\\]"),
+    ("[\\", "\\]"),
+    ("[\\", "/]"),
+    ("(-", "-)"),
+    ("(", ")"),
+    ("[", "]"),
+    ("{", "}"),
+    (">", "]"),
+];
+
+/// Statements that don't declare nodes or links.
+const DIRECTIVES: [&str; 10] = [
+    "style",
+    "classDef",
+    "class",
+    "linkStyle",
+    "click",
+    "subgraph",
+    "end",
+    "direction",
+    "accTitle",
+    "accDescr",
+];
+
+lazy_static! {
+    static ref NODE_ID: Regex = Regex::new(r"^[\w$]+").unwrap();
+    /// `-->`, `---`, `-.->`, `==>`, `<-->`, `--x`, optionally followed by `|text|`.
+    static ref LINK: Regex =
+        Regex::new(r"^[<xo]?(?:-{2,}|={2,}|-\.+-)(?:>|[xo]\s)?\s*(?:\|[^|]*\|)?").unwrap();
+    /// `-- text -->`, `== text ==>`, `-. text .->`.
+    static ref TEXT_LINK: Regex =
+        Regex::new(r"^[<xo]?(?:--|==|-\.)\s[^>]*?\s?(?:-{2,}|={2,}|\.-+)(?:>|[xo]\s)?").unwrap();
+    static ref ENTITY: Regex = Regex::new(r"#(\w+);|<br\s*/?>").unwrap();
+}
+
+struct Node {
+    id: String,
+    label: Option<String>,
+    /// Where the node first appears.
+    line: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Flowchart {
+    nodes: Vec<Node>,
+    ids: HashMap<String, usize>,
+    header: bool,
+    /// Within the lines of an `accDescr { … }` block.
+    description: bool,
+}
+
+impl Flowchart {
+    fn statement(&mut self, line: usize, statement: &str) -> Result<(), MermaidError> {
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        if !self.header && self.nodes.is_empty() && matches!(keyword, "graph" | "flowchart") {
+            self.header = true;
+            return Ok(());
+        }
+        if self.description {
+            self.description = !statement.contains('}');
+            return Ok(());
+        }
+        // `accTitle: text`, `accDescr: text` and `accDescr { text }` may leave out the spaces.
+        let keyword = keyword.split([':', '{']).next().unwrap_or_default();
+        if DIRECTIVES.contains(&keyword) {
+            self.description =
+                keyword == "accDescr" && statement.contains('{') && !statement.contains('}');
+            return Ok(());
+        }
+
+        let syntax = || SyntaxSnafu { line, statement };
+        let mut rest = statement;
+        let mut parents: Vec<usize> = Vec::new();
+        loop {
+            let mut group = Vec::new();
+            loop {
+                let (node, after) = self.node(line, rest).with_context(syntax)?;
+                group.push(node);
+                rest = after.trim_start();
+                let Some(after) = rest.strip_prefix('&') else { break };
+                rest = after.trim_start();
+            }
+            for &parent in &parents {
+                for &child in &group {
+                    self.link(line, parent, child)?;
+                }
+            }
+            if rest.is_empty() {
+                return Ok(());
+            }
+
+            let link = TEXT_LINK.find(rest).or_else(|| LINK.find(rest)).with_context(syntax)?;
+            rest = rest[link.end()..].trim_start();
+            parents = group;
+        }
+    }
+
+    /// Reads a node such as `A`, `A[text]` or `A(("text"))`, returning it and the rest.
+    fn node<'s>(&mut self, line: usize, s: &'s str) -> Option<(usize, &'s str)> {
+        let id = NODE_ID.find(s)?.as_str();
+        let mut rest = &s[id.len()..];
+
+        let mut label = None;
+        if let Some(&(open, _)) = SHAPES.iter().find(|(open, _)| rest.starts_with(open)) {
+            let inner = &rest[open.len()..];
+            // Trapezoids share their openers, the closer tells them apart.
+            let mut closes = SHAPES.iter().filter(|(o, _)| *o == open).map(|&(_, close)| close);
+            let (text, after) = if let Some(quoted) = inner.strip_prefix('"') {
+                let end = quoted.find('"')?;
+                let after = &quoted[end + 1..];
+                (&quoted[..end], closes.find_map(|close| after.strip_prefix(close))?)
+            } else {
+                let (end, close) =
+                    closes.filter_map(|close| Some((inner.find(close)?, close))).min()?;
+                (inner[..end].trim(), &inner[end + close.len()..])
+            };
+            label = Some(unescape(text));
+            rest = after;
+        }
+        if let Some(class) = rest.strip_prefix(":::") {
+            rest = &class[NODE_ID.find(class)?.end()..];
+        }
+
+        let nodes = &mut self.nodes;
+        let index = *self.ids.entry(id.to_owned()).or_insert_with(|| {
+            let id = id.to_owned();
+            nodes.push(Node { id, label: None, line, parent: None, children: Vec::new() });
+            nodes.len() - 1
+        });
+        let node = &mut self.nodes[index];
+        if node.label.is_none() {
+            node.label = label;
+        }
+        Some((index, rest))
+    }
+
+    fn link(&mut self, line: usize, parent: usize, child: usize) -> Result<(), MermaidError> {
+        let node = self.nodes[child].id.clone();
+        ensure!(self.nodes[child].parent.is_none(), SecondParentSnafu { line, node });
+
+        let mut ancestor = Some(parent);
+        while let Some(index) = ancestor {
+            ensure!(index != child, CycleSnafu { line, node });
+            ancestor = self.nodes[index].parent;
+        }
+
+        self.nodes[child].parent = Some(parent);
+        self.nodes[parent].children.push(child);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Tree<String>, MermaidError> {
+        let mut roots = self.nodes.iter().filter(|node| node.parent.is_none());
+        let root = roots.next().context(EmptySnafu)?;
+        if let Some(second) = roots.next() {
+            return SecondRootSnafu { line: second.line, node: &second.id, root: &root.id }.fail();
+        }
+        let root = self.ids[&root.id];
+        Ok(self.tree(root))
+    }
+
+    fn tree(&mut self, index: usize) -> Tree<String> {
+        let node = &mut self.nodes[index];
+        let label = node.label.take().unwrap_or_else(|| node.id.clone());
+        let children = std::mem::take(&mut node.children);
+        Tree::new(label).with_leaves(children.into_iter().map(|child| self.tree(child)))
+    }
+}
+
+/// Undoes [`Escaped`], reading entity codes such as `#quot;` or `#35;` and `<br>` line breaks.
+pub(crate) fn unescape(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures<'_>| {
+            let Some(entity) = captures.get(1) else { return "\n".to_owned() };
+            let c = match entity.as_str() {
+                "quot" => Some('"'),
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                code => code.parse().ok().and_then(char::from_u32),
+            };
+            c.map_or_else(|| captures[0].to_owned(), String::from)
+        })
+        .into_owned()
+}
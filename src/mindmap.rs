@@ -0,0 +1,311 @@
+//! [Mermaid](https://mermaid.js.org/syntax/mindmap.html) mindmaps of trees.
+
+use std::fmt::{self, Display, Write};
+
+use snafu::{ensure, OptionExt};
+
+use crate::{
+    mermaid::{mermaid_block, unescape, EmptySnafu, Escaped, SecondRootSnafu, SyntaxSnafu},
+    MermaidError, Tree,
+};
+
+// ---------------------------------------------------------
+
+/// The outline drawn around a mindmap node.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum MindmapShape {
+    /// Plain text, without an ID.
+    #[default]
+    Default,
+    /// `id[text]`
+    Square,
+    /// `id(text)`
+    Rounded,
+    /// `id((text))`
+    Circle,
+    /// `id))text((`
+    Bang,
+    /// `id)text(`
+    Cloud,
+    /// `id{{text}}`
+    Hexagon,
+}
+
+/// Shapes as `(shape, open, close)`, ordered so that `((` and `))` win over `(` and `)`.
+const SHAPES: [(MindmapShape, &str, &str); 6] = [
+    (MindmapShape::Circle, "((", "))"),
+    (MindmapShape::Bang, "))", "(("),
+    (MindmapShape::Hexagon, "{{", "}}"),
+    (MindmapShape::Rounded, "(", ")"),
+    (MindmapShape::Cloud, ")", "("),
+    (MindmapShape::Square, "[", "]"),
+];
+
+/// A mindmap node, with the shape and decorations that a terminal rendering leaves out.
+///
+/// It displays as its text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MindmapNode {
+    /// The ID written before the shape, such as `root` in `root((text))`.
+    pub id: Option<String>,
+    pub text: String,
+    pub shape: MindmapShape,
+    /// Icon classes, such as `fa fa-book` in `::icon(fa fa-book)`.
+    pub icon: Option<String>,
+    /// CSS classes, such as `urgent large` in `:::urgent large`.
+    pub class: Option<String>,
+}
+
+impl MindmapNode {
+    /// Creates a plain node.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), ..Default::default() }
+    }
+
+    pub fn with_shape(mut self, shape: MindmapShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
+
+impl Display for MindmapNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<&str> for MindmapNode {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for MindmapNode {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A label that can be drawn as a mindmap node, as plain text unless it says otherwise.
+pub trait MindmapLabel: Display {
+    fn id(&self) -> Option<&str> {
+        None
+    }
+
+    fn shape(&self) -> MindmapShape {
+        MindmapShape::Default
+    }
+
+    fn icon(&self) -> Option<&str> {
+        None
+    }
+
+    fn class(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl MindmapLabel for String {}
+
+impl MindmapLabel for &str {}
+
+impl MindmapLabel for MindmapNode {
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn shape(&self) -> MindmapShape {
+        self.shape
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: MindmapLabel,
+{
+    /// Draws the tree as a Mermaid mindmap, indenting each level by two spaces.
+    ///
+    /// Shaped nodes without an ID get one from their path like [`Tree::to_mermaid`]. Plain text
+    /// that Mermaid would read as a shape, such as `f(x)`, is written in a square instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{MindmapNode, MindmapShape, Tree};
+    ///
+    /// let root = MindmapNode::new("plans").with_shape(MindmapShape::Circle);
+    /// let tree = Tree::new(root).with_leaves([MindmapNode::new("travel").with_icon("fa fa-plane")]);
+    /// assert_eq!(tree.to_mindmap(), "mindmap\n  n((plans))\n    travel\n      ::icon(fa fa-plane)\n");
+    /// ```
+    pub fn to_mindmap(&self) -> String {
+        let mut out = String::from("mindmap\n");
+        // Writing to a `String` only fails if a label's `Display` does.
+        let _ = write_node(&mut out, self, "n", 1);
+        out
+    }
+}
+
+fn write_node<D, W>(out: &mut W, tree: &Tree<D>, id: &str, depth: usize) -> fmt::Result
+where
+    D: MindmapLabel,
+    W: Write,
+{
+    let indent = "  ".repeat(depth);
+    let label = &tree.root;
+    let text = label.to_string();
+
+    let mut shape = label.shape();
+    if shape == MindmapShape::Default && is_ambiguous(&text) {
+        shape = MindmapShape::Square;
+    }
+    match SHAPES.iter().find(|(s, ..)| *s == shape) {
+        Some((_, open, close)) => {
+            let id = label.id().unwrap_or(id);
+            let quote = text.trim() != text || text.contains(['(', ')', '[', ']', '{', '}']);
+            let quote = if quote { "\"" } else { "" };
+            let text = Escaped(&text);
+            writeln!(out, "{}{}{}{}{}{}{}", indent, id, open, quote, text, quote, close)?;
+        }
+        None => writeln!(out, "{}{}", indent, Escaped(&text))?,
+    }
+    if let Some(icon) = label.icon() {
+        writeln!(out, "{}  ::icon({})", indent, icon)?;
+    }
+    if let Some(class) = label.class() {
+        writeln!(out, "{}  :::{}", indent, class)?;
+    }
+
+    for (i, leaf) in tree.leaves.iter().enumerate() {
+        write_node(out, leaf, &format!("{}_{}", id, i), depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Whether plain `text` would be read back as something else.
+fn is_ambiguous(text: &str) -> bool {
+    text.is_empty()
+        || text.trim() != text
+        || text.starts_with("::")
+        || parse_node(text).shape != MindmapShape::Default
+}
+
+// ---------------------------------------------------------
+
+impl Tree<MindmapNode> {
+    /// Reads a Mermaid mindmap, or the first mindmap in the `mermaid` code blocks of a Markdown
+    /// document.
+    ///
+    /// Like Mermaid, a node's parent is the closest node above it that is indented less.
+    /// `::icon()` and `:::class` lines decorate the node above them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{MindmapShape, Tree};
+    ///
+    /// let text = "mindmap\n  root((plans))\n    travel\n      ::icon(fa fa-plane)\n    work\n";
+    /// let tree = Tree::from_mindmap(text).unwrap();
+    /// assert_eq!(tree.to_string(), "plans\n├── travel\n└── work\n");
+    /// assert_eq!(tree.root.shape, MindmapShape::Circle);
+    /// assert_eq!(tree.leaves[0].root.icon.as_deref(), Some("fa fa-plane"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the offending line if a decoration has no node,
+    /// or if there is more than one node at the root's indentation.
+    pub fn from_mindmap(text: &str) -> Result<Self, MermaidError> {
+        let (offset, block) = mermaid_block(text, |keyword| keyword == "mindmap");
+        // The indentation and subtree of the most recent node at each level.
+        let mut path: Vec<(usize, Tree<MindmapNode>)> = Vec::new();
+        let mut header = false;
+
+        for (line, text) in (offset + 1..).zip(block.lines()) {
+            let text = text.split("%%").next().unwrap_or_default().trim_end();
+            let statement = text.trim_start();
+            if statement.is_empty() {
+                continue;
+            }
+            if !header && path.is_empty() && statement == "mindmap" {
+                header = true;
+                continue;
+            }
+
+            if let Some(decoration) = statement.strip_prefix("::") {
+                let syntax = || SyntaxSnafu { line, statement };
+                let (_, node) = path.last_mut().with_context(syntax)?;
+                if let Some(class) = decoration.strip_prefix(':') {
+                    node.root.class = Some(class.trim().to_owned());
+                } else {
+                    let icon = decoration.strip_prefix("icon(").and_then(|s| s.strip_suffix(')'));
+                    node.root.icon = Some(icon.with_context(syntax)?.trim().to_owned());
+                }
+                continue;
+            }
+
+            let indent = text.len() - statement.len();
+            let node = parse_node(statement);
+            if let Some((root_indent, root)) = path.first() {
+                ensure!(
+                    indent > *root_indent,
+                    SecondRootSnafu { line, node: &node.text, root: &root.root.text }
+                );
+            }
+            while matches!(path.last(), Some((level, _)) if *level >= indent) {
+                close(&mut path);
+            }
+            path.push((indent, Tree::new(node)));
+        }
+
+        while path.len() > 1 {
+            close(&mut path);
+        }
+        path.pop().map(|(_, root)| root).context(EmptySnafu)
+    }
+}
+
+/// Moves the last node of `path` into its parent.
+fn close(path: &mut Vec<(usize, Tree<MindmapNode>)>) {
+    if let Some((_, leaf)) = path.pop() {
+        if let Some((_, parent)) = path.last_mut() {
+            parent.leaves.push(leaf);
+        }
+    }
+}
+
+/// Reads a node such as `text`, `id[text]` or `id(("text"))`.
+fn parse_node(statement: &str) -> MindmapNode {
+    for &(shape, open, close) in &SHAPES {
+        let Some(inner) = statement.strip_suffix(close) else { continue };
+        let Some(start) = inner.find(open) else { continue };
+        let id = &inner[..start];
+        if !id.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            continue;
+        }
+        let text = unquote(inner[start + open.len()..].trim());
+        let id = (!id.is_empty()).then(|| id.to_owned());
+        return MindmapNode { id, text: unescape(text), shape, ..Default::default() };
+    }
+    MindmapNode::new(unescape(statement))
+}
+
+/// Strips the quotes of a string and then the backticks of a Markdown string.
+fn unquote(text: &str) -> &str {
+    let text = text.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(text);
+    text.strip_prefix('`').and_then(|s| s.strip_suffix('`')).unwrap_or(text)
+}
@@ -0,0 +1,359 @@
+//! Reading trees from text: rendered trees, so that pasted output can be edited and loaded
+//! again, indented outlines and Markdown lists.
+
+use std::str::FromStr;
+
+use snafu::{ensure, OptionExt, Snafu};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{GlyphPalette, GlyphPreset, Tree};
+
+// ---------------------------------------------------------
+
+#[derive(Debug, Snafu)]
+pub enum ParseError {
+    #[snafu(display("there is no tree to parse"))]
+    Empty,
+    #[snafu(display(
+        "line {line}, column {column}: expected a branch glyph or the continuation of a label"
+    ))]
+    Indentation { line: usize, column: usize },
+    #[snafu(display("line {line}, column {column}: indentation doesn't match any outer level"))]
+    Dedent { line: usize, column: usize },
+    #[snafu(display(
+        "line {line}, column {column}: a tree has a single root, indent this line below it"
+    ))]
+    SecondRoot { line: usize, column: usize },
+}
+
+impl Tree<String> {
+    /// Reads text rendered with `glyphs` back into a tree.
+    ///
+    /// Lines behind the skip glyphs of a node are read as more lines of its label, the way
+    /// multi-line and wrapped labels are drawn. Trailing whitespace that editors strip from lines
+    /// holding nothing but glyphs is not required.
+    ///
+    /// With palettes whose item and skip prefixes look alike, such as
+    /// [`GlyphPalette::indent_only`], such lines are read as children instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{GlyphPalette, Tree};
+    ///
+    /// let text = "foo\n|-- bar\n|   `-- two\n|       lines\n`-- baz\n";
+    /// let tree = Tree::parse(text, &GlyphPalette::ascii()).unwrap();
+    /// assert_eq!(tree.leaves[0].leaves[0].root, "two\nlines");
+    /// assert_eq!(tree.with_palette(GlyphPalette::ascii()).to_string(), text);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `text` is blank, or if a line is neither a node nor
+    /// the continuation of one.
+    pub fn parse(text: &str, glyphs: &GlyphPalette) -> Result<Self, ParseError> {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while matches!(lines.last(), Some(line) if line.trim().is_empty()) {
+            lines.pop();
+        }
+        let (first, rest) = lines.split_first().context(EmptySnafu)?;
+
+        let mut root = Tree::new((*first).to_owned());
+        // The most recent node at each depth below the root, and whether it was the last child.
+        let mut path: Vec<(Tree<String>, bool)> = Vec::new();
+
+        for (number, line) in (2_usize..).zip(rest) {
+            // What is left of the line after each of the matching ancestor columns.
+            let mut columns: Vec<&str> = vec![line];
+            for (_, last) in &path {
+                match strip_column(columns[columns.len() - 1], glyphs.skip(*last)) {
+                    Some(rest) => columns.push(rest),
+                    None => break,
+                }
+            }
+
+            let item = columns.iter().enumerate().rev().find_map(|(depth, rest)| {
+                [true, false].into_iter().find_map(|last| {
+                    strip_column(rest, glyphs.item(last)).map(|label| (depth, last, label))
+                })
+            });
+            match item {
+                Some((depth, last, label)) => {
+                    close(&mut root, &mut path, depth);
+                    path.push((Tree::new(label.to_owned()), last));
+                }
+                // All ancestor columns and the node's own skip column matched.
+                None if columns.len() == path.len() + 1 => {
+                    let node = match path.last_mut() {
+                        Some((leaf, _)) => leaf,
+                        None => &mut root,
+                    };
+                    node.root.push('\n');
+                    node.root.push_str(columns[columns.len() - 1]);
+                }
+                None => {
+                    let rest = columns[columns.len() - 1];
+                    let column = line[..line.len() - rest.len()].width() + 1;
+                    return IndentationSnafu { line: number, column }.fail();
+                }
+            }
+        }
+
+        close(&mut root, &mut path, 0);
+        Ok(root)
+    }
+
+    /// Reads an outline indented with spaces or tabs, one node per line.
+    ///
+    /// The first line is the root and every other line has to be indented below it. Blank lines
+    /// are skipped. A line is a child of the line above if its indentation starts with the
+    /// indentation of that line, so tabs and spaces can't be mixed from one level to the next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::from_outline("fruit\n  apple\n    granny smith\n  pear\n").unwrap();
+    /// assert_eq!(tree.to_string(), "fruit\n├── apple\n│   └── granny smith\n└── pear\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there are no lines, or with the line and column of
+    /// the first line that doesn't fit the indentation of the lines above it.
+    pub fn from_outline(text: &str) -> Result<Self, ParseError> {
+        let mut outline = Outline::default();
+        for (number, line) in (1_usize..).zip(text.lines()) {
+            let label = line.trim_start();
+            if label.is_empty() {
+                continue;
+            }
+            let indent = &line[..line.len() - label.len()];
+            outline.push(number, indent.to_owned(), label.trim_end().to_owned())?;
+        }
+        outline.finish()
+    }
+
+    /// Reads the first list of a Markdown document, nested lists becoming children.
+    ///
+    /// A heading above the list becomes the root, and every top-level item one of its children.
+    /// Without one, the list has to have a single top-level item. Lines that continue an item
+    /// become more lines of its label, and the list ends at the first paragraph after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let markdown = "# Fruit\n\nSome intro.\n\n- apple\n  1. granny smith\n- pear\n";
+    /// let tree = Tree::from_markdown(markdown).unwrap();
+    /// assert_eq!(tree.to_string(), "Fruit\n├── apple\n│   └── granny smith\n└── pear\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is no list, or with the line and column of
+    /// the first item that doesn't fit the indentation of the items above it.
+    pub fn from_markdown(text: &str) -> Result<Self, ParseError> {
+        let mut outline = Outline::default();
+        let mut in_list = false;
+        let mut after_blank = false;
+        for (number, line) in (1_usize..).zip(text.lines()) {
+            let content = line.trim_start();
+            let indent = &line[..line.len() - content.len()];
+            if content.is_empty() {
+                after_blank = true;
+                continue;
+            }
+
+            if let Some(label) = list_item(content) {
+                // Tabs stop every 4 columns in Markdown.
+                let indent = indent.replace('\t', "    ");
+                outline.push(number, indent, label.trim_end().to_owned())?;
+                in_list = true;
+            } else if in_list && indent.is_empty() && after_blank {
+                break;
+            } else if in_list {
+                outline.continue_label(content.trim_end());
+            } else if let Some(heading) = heading(content) {
+                if outline.root.is_none() {
+                    outline.root = Some(Tree::new(heading.to_owned()));
+                }
+            }
+            after_blank = false;
+        }
+        outline.finish()
+    }
+}
+
+/// Reads a tree drawn with any of the [`GlyphPreset`]s.
+///
+/// The preset is recognized by the last child of the root, which starts a line with its
+/// `last_item` glyph.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::Tree;
+///
+/// let tree: Tree<String> = "foo\n┣━━ bar\n┗━━ baz\n".parse().unwrap();
+/// assert_eq!(tree.leaves[1].root, "baz");
+/// ```
+impl FromStr for Tree<String> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const PRESETS: [GlyphPreset; 6] = [
+            GlyphPreset::Box,
+            GlyphPreset::Ascii,
+            GlyphPreset::Rounded,
+            GlyphPreset::Heavy,
+            GlyphPreset::Double,
+            GlyphPreset::Indent,
+        ];
+        let glyphs = PRESETS
+            .into_iter()
+            .map(GlyphPalette::from)
+            .find(|glyphs| s.lines().any(|line| strip_column(line, glyphs.item(true)).is_some()))
+            .unwrap_or_default();
+        Tree::parse(s, &glyphs)
+    }
+}
+
+// ---------------------------------------------------------
+
+/// Builds a tree from lines that are nested by their indentation.
+#[derive(Default)]
+struct Outline {
+    root: Option<Tree<String>>,
+    /// Indentation of the root, `None` if everything nests below it, like under a heading.
+    root_indent: Option<String>,
+    /// The most recent node at each level of indentation below the root.
+    path: Vec<(Tree<String>, String)>,
+}
+
+impl Outline {
+    fn push(&mut self, line: usize, indent: String, label: String) -> Result<(), ParseError> {
+        let Some(root) = &mut self.root else {
+            self.root = Some(Tree::new(label));
+            self.root_indent = Some(indent);
+            return Ok(());
+        };
+
+        if let Some(root_indent) = &self.root_indent {
+            let column = indent.chars().count() + 1;
+            ensure!(
+                indent.starts_with(root_indent.as_str()) && indent.len() > root_indent.len(),
+                SecondRootSnafu { line, column }
+            );
+        }
+
+        let mut dedented = None;
+        while let Some((_, level)) = self.path.last() {
+            if indent.starts_with(level.as_str()) {
+                break;
+            }
+            dedented = Some(common_prefix(&indent, level));
+            let depth = self.path.len() - 1;
+            close(root, &mut self.path, depth);
+        }
+
+        match self.path.last() {
+            Some((_, level)) if *level == indent => {
+                let depth = self.path.len() - 1;
+                close(root, &mut self.path, depth);
+            }
+            _ => {
+                if let Some(column) = dedented {
+                    return DedentSnafu { line, column: column + 1 }.fail();
+                }
+            }
+        }
+        self.path.push((Tree::new(label), indent));
+        Ok(())
+    }
+
+    /// Adds another line to the label of the most recent node.
+    fn continue_label(&mut self, line: &str) {
+        let node = match (self.path.last_mut(), &mut self.root) {
+            (Some((leaf, _)), _) => leaf,
+            (None, Some(root)) => root,
+            (None, None) => return,
+        };
+        node.root.push('\n');
+        node.root.push_str(line);
+    }
+
+    fn finish(mut self) -> Result<Tree<String>, ParseError> {
+        let mut root = self.root.context(EmptySnafu)?;
+        close(&mut root, &mut self.path, 0);
+        Ok(root)
+    }
+}
+
+/// The number of characters `a` and `b` start with in common.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+}
+
+/// The text of a Markdown list item, such as `- apple` or `1. apple`.
+fn list_item(line: &str) -> Option<&str> {
+    let rest = if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        // Ordered items have up to 9 digits.
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        line[digits..].strip_prefix(['.', ')'])?
+    };
+    match rest.strip_prefix([' ', '\t']) {
+        Some(label) => Some(label.trim_start()),
+        None => rest.is_empty().then_some(""),
+    }
+}
+
+/// The text of an ATX heading, such as `# Fruit`.
+fn heading(line: &str) -> Option<&str> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    match line[hashes..].strip_prefix([' ', '\t']) {
+        Some(text) => Some(text.trim().trim_end_matches('#').trim_end()),
+        None => line[hashes..].is_empty().then_some(""),
+    }
+}
+
+/// Attaches the nodes deeper than `depth` to their parents.
+fn close<T>(root: &mut Tree<String>, path: &mut Vec<(Tree<String>, T)>, depth: usize) {
+    while path.len() > depth {
+        if let Some((leaf, _)) = path.pop() {
+            match path.last_mut() {
+                Some((parent, _)) => parent.leaves.push(leaf),
+                None => root.leaves.push(leaf),
+            }
+        }
+    }
+}
+
+/// The rest of `line` after a `(glyph, indent)` column.
+///
+/// A line that ends within the column is accepted as long as only whitespace is missing.
+fn strip_column<'l>(line: &'l str, (glyph, indent): (&str, &str)) -> Option<&'l str> {
+    if let Some(rest) = line.strip_prefix(glyph).and_then(|rest| rest.strip_prefix(indent)) {
+        return Some(rest);
+    }
+    let trimmed = match line.strip_prefix(glyph) {
+        Some(rest) => {
+            matches!(indent.strip_prefix(rest), Some(missing) if missing.trim().is_empty())
+        }
+        None => {
+            matches!(glyph.strip_prefix(line), Some(missing) if missing.trim().is_empty())
+                && indent.trim().is_empty()
+        }
+    };
+    trimmed.then_some("")
+}
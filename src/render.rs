@@ -0,0 +1,533 @@
+//! Streaming renderer behind [`Display`] for [`Tree`] and [`Tree::write_to`].
+//!
+//! Walks the tree depth-first with an explicit stack of the ancestors of the current node, so
+//! memory grows with the depth of the tree rather than with its size. Trees with annotations are
+//! walked twice, the first time to find where the annotation column goes, and once more before
+//! that if lines are wrapped or cut, to take the column's room out of their width.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Write},
+    io, slice,
+};
+
+use console::Style;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{GlyphPalette, LineWidth, Multiline, RenderOptions, Tree};
+
+// ---------------------------------------------------------
+
+pub(crate) fn render<D, W>(tree: &Tree<D>, w: &mut W) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    render_colored(tree, w, tree.options().color.enabled())
+}
+
+/// Renders without colors whatever [`RenderOptions::color`] says, for output that isn't a
+/// terminal.
+pub(crate) fn render_plain<D, W>(tree: &Tree<D>, w: &mut W) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    render_colored(tree, w, false)
+}
+
+fn render_colored<D, W>(tree: &Tree<D>, w: &mut W, colored: bool) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    let mut renderer = Renderer::new(tree, colored);
+    let annotations = match tree.has_annotations() {
+        true => {
+            if renderer.wrap.is_some() || renderer.truncate.is_some() {
+                // Two spaces and the widest annotation, rows cut to make room only lose theirs.
+                let width = renderer.measure().width;
+                renderer.reserve(2 + width);
+            }
+            Some(renderer.measure())
+        }
+        false => None,
+    };
+    renderer.rows(&mut |row| renderer.write_row(w, &row, annotations))
+}
+
+pub(crate) fn write_to<D, W>(tree: &Tree<D>, writer: W) -> io::Result<()>
+where
+    D: Display,
+    W: io::Write,
+{
+    let mut adapter = IoWriter { inner: writer, error: None };
+    match render(tree, &mut adapter) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) => Err(adapter.error.take().unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "formatting a tree label failed")
+        })),
+    }
+}
+
+/// Forwards `fmt::Write` to an `io::Write`, keeping the I/O error that `fmt::Error` can't carry.
+struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W> Write for IoWriter<W>
+where
+    W: io::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+// ---------------------------------------------------------
+
+/// Everything about the options that is resolved once per rendering.
+struct Renderer<'t, D>
+where
+    D: Display,
+{
+    tree: &'t Tree<D>,
+    options: &'t RenderOptions,
+    palette: &'t GlyphPalette,
+    /// Columns taken by each level of indentation.
+    width: usize,
+    colored: bool,
+    depth_styles: Vec<Style>,
+    elision_style: Option<Style>,
+    wrap: Option<usize>,
+    truncate: Option<usize>,
+}
+
+/// One line of output.
+struct Row<'r, 't, D>
+where
+    D: Display,
+{
+    columns: &'r [Frame<'t, D>],
+    /// `None` on the lines of the root.
+    prefix: Option<(&'r str, &'r str)>,
+    label: &'r str,
+    style: Option<&'r Style>,
+    /// Only on the first line of a node.
+    annotation: Option<&'r str>,
+    /// The columns the whole line is cut to when not even its glyphs fit, the label being left
+    /// out.
+    cut: Option<usize>,
+}
+
+/// Where the annotation column starts, and how wide it is.
+#[derive(Clone, Copy, Default)]
+struct Annotations {
+    indent: usize,
+    width: usize,
+}
+
+impl<'t, D> Renderer<'t, D>
+where
+    D: Display,
+{
+    fn new(tree: &'t Tree<D>, colored: bool) -> Self {
+        let options = tree.options();
+        let depth_styles: Vec<Style> = match colored {
+            true => options.depth_styles.iter().map(|s| s.to_style().force_styling(true)).collect(),
+            false => Vec::new(),
+        };
+        Self {
+            tree,
+            options,
+            palette: &options.glyphs,
+            width: options.glyphs.width(),
+            colored,
+            depth_styles,
+            elision_style: Some(Style::new().dim().force_styling(true)).filter(|_| colored),
+            wrap: options.wrap.and_then(LineWidth::columns),
+            truncate: options.truncate.and_then(LineWidth::columns),
+        }
+    }
+
+    /// Keeps `columns` of the widths that lines are wrapped and cut to for the annotations.
+    fn reserve(&mut self, columns: usize) {
+        self.wrap = self.wrap.map(|wrap| wrap.saturating_sub(columns));
+        self.truncate = self.truncate.map(|truncate| truncate.saturating_sub(columns));
+    }
+
+    fn label_style(&self, leaf: &Tree<D>) -> Option<Style> {
+        leaf.style.as_ref().filter(|_| self.colored).map(|s| s.to_style().force_styling(true))
+    }
+
+    /// Visits every line of output, top to bottom.
+    fn rows<F>(&self, visit: &mut F) -> fmt::Result
+    where
+        F: FnMut(Row<'_, 't, D>) -> fmt::Result,
+    {
+        let Self { tree, options, palette, width, wrap, truncate, .. } = *self;
+
+        let root: String = tree.root.to_string();
+        let style = self.label_style(tree);
+        for (i, line) in label_lines(&root, tree.multiline, wrap).into_iter().enumerate() {
+            visit(Row {
+                columns: &[],
+                prefix: None,
+                label: &fit(line, truncate),
+                style: style.as_ref(),
+                annotation: tree.annotation.as_deref().filter(|_| i == 0),
+                cut: None,
+            })?;
+        }
+
+        // `stack[0]` holds the children of the root, every other frame also owns an indentation
+        // column for the descendants of its node.
+        let mut stack: Vec<Frame<'t, D>> = Vec::new();
+        if !tree.leaves.is_empty() {
+            stack.push(Frame::new(tree, (true, palette), 0, options));
+        }
+
+        while let Some(frame) = stack.last_mut() {
+            let Some((last, entry)) = frame.next() else {
+                stack.pop();
+                continue;
+            };
+            let columns = &stack[1..];
+            let indentation = (columns.len() + 1) * width;
+            // From this depth on, the glyphs alone fill the line.
+            let cut = truncate.filter(|&truncate| indentation >= truncate);
+            let truncate = truncate.map(|truncate| truncate.saturating_sub(indentation));
+
+            let leaf: &Tree<D> = match entry {
+                Entry::Leaf(leaf) => leaf,
+                Entry::Elided(elision) => {
+                    let label = elision.to_string();
+                    visit(Row {
+                        columns,
+                        prefix: Some(palette.item(last)),
+                        label: &fit(&label, truncate),
+                        style: self.elision_style.as_ref(),
+                        annotation: None,
+                        cut: cut.filter(|&cut| indentation + label.width() > cut),
+                    })?;
+                    continue;
+                }
+            };
+            let glyphs: &GlyphPalette = leaf.glyphs.as_ref().unwrap_or(palette);
+            let style = self.label_style(leaf);
+
+            let root: String = leaf.root.to_string();
+            let room = wrap.map(|wrap| wrap.saturating_sub(indentation));
+            for (i, line) in label_lines(&root, leaf.multiline, room).into_iter().enumerate() {
+                let cut = cut.filter(|&cut| indentation + line.width() > cut);
+                visit(Row {
+                    columns,
+                    prefix: Some(if i == 0 { glyphs.item(last) } else { glyphs.skip(last) }),
+                    label: &fit(line, truncate),
+                    style: style.as_ref(),
+                    annotation: leaf.annotation.as_deref().filter(|_| i == 0 && cut.is_none()),
+                    cut,
+                })?;
+            }
+
+            if !leaf.leaves.is_empty() {
+                let depth = stack.len();
+                stack.push(Frame::new(leaf, (last, glyphs), depth, options));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lines up the annotations one column past the widest annotated row.
+    fn measure(&self) -> Annotations {
+        let mut annotations = Annotations::default();
+        // Measuring never fails, only writing does.
+        let _ = self.rows(&mut |row| {
+            if let Some(annotation) = row.annotation {
+                annotations.indent = annotations.indent.max(self.row_width(&row));
+                annotations.width = annotations.width.max(annotation.width());
+            }
+            Ok(())
+        });
+        annotations
+    }
+
+    /// Columns taken by the row without its annotation.
+    fn row_width(&self, row: &Row<'_, 't, D>) -> usize {
+        (row.columns.len() + usize::from(row.prefix.is_some())) * self.width + row.label.width()
+    }
+
+    fn write_row<W>(
+        &self,
+        w: &mut W,
+        row: &Row<'_, 't, D>,
+        annotations: Option<Annotations>,
+    ) -> fmt::Result
+    where
+        W: Write,
+    {
+        let Self { width, .. } = *self;
+        if let Some(cut) = row.cut {
+            self.write_cut(w, row, cut)?;
+            return writeln!(w);
+        }
+        write_columns(w, row.columns, width, &self.depth_styles)?;
+        if let Some(prefix) = row.prefix {
+            let style = depth_style(&self.depth_styles, row.columns.len());
+            write_styled(w, Fit { prefix, width }, style)?;
+        }
+        write_styled(w, row.label, row.style)?;
+
+        if let (Some(annotation), Some(column)) = (row.annotation, annotations) {
+            // Two spaces between the label and the annotation, which is aligned to the right.
+            let pad = column.indent - self.row_width(row) + 2 + column.width - annotation.width();
+            write!(w, "{:pad$}{}", "", annotation, pad = pad)?;
+        }
+        writeln!(w)
+    }
+
+    /// Writes the glyphs of `row` cut to `cut` columns, the last one being `…`.
+    fn write_cut<W>(&self, w: &mut W, row: &Row<'_, 't, D>, cut: usize) -> fmt::Result
+    where
+        W: Write,
+    {
+        let Self { width, .. } = *self;
+        let skips = row.columns.iter().map(|frame| frame.column.1.skip(frame.column.0));
+        let mut left = cut.saturating_sub(1);
+        for (depth, prefix) in skips.chain(row.prefix).enumerate() {
+            let glyphs = Fit { prefix, width }.to_string();
+            let shown = take_columns(&glyphs, left);
+            write_styled(w, shown, depth_style(&self.depth_styles, depth))?;
+            left -= shown.width();
+            if shown.len() < glyphs.len() {
+                break;
+            }
+        }
+        if cut > 0 {
+            write_styled(w, "…", self.elision_style.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------
+
+/// Whether an ancestor was the last of its siblings, and the glyphs it was drawn with.
+type Column<'t> = (bool, &'t GlyphPalette);
+
+/// The children of a node that are still to be drawn.
+struct Frame<'t, D>
+where
+    D: Display,
+{
+    leaves: slice::Iter<'t, Tree<D>>,
+    /// Drawn after the last of `leaves`.
+    elided: Option<Elision>,
+    column: Column<'t>,
+}
+
+impl<'t, D> Frame<'t, D>
+where
+    D: Display,
+{
+    /// The children of `parent`, which sits at `depth`.
+    fn new(parent: &'t Tree<D>, column: Column<'t>, depth: usize, options: &RenderOptions) -> Self {
+        if matches!(options.max_depth, Some(max) if depth >= max) {
+            let elided = Some(Elision::Descendants(parent.count_descendants()));
+            return Self { leaves: [].iter(), elided, column };
+        }
+
+        let len = parent.leaves.len();
+        let shown = options.max_children.map_or(len, |max| max.min(len));
+        let elided = Some(Elision::Siblings(len - shown)).filter(|_| shown < len);
+        Self { leaves: parent.leaves[..shown].iter(), elided, column }
+    }
+
+    /// The next entry, and whether it is the last one.
+    fn next(&mut self) -> Option<(bool, Entry<'t, D>)> {
+        if let Some(leaf) = self.leaves.next() {
+            let last = self.leaves.len() == 0 && self.elided.is_none();
+            return Some((last, Entry::Leaf(leaf)));
+        }
+        self.elided.take().map(|elision| (true, Entry::Elided(elision)))
+    }
+}
+
+enum Entry<'t, D>
+where
+    D: Display,
+{
+    Leaf(&'t Tree<D>),
+    Elided(Elision),
+}
+
+/// Summary line drawn in place of the nodes hidden by [`RenderOptions::max_depth`] and
+/// [`RenderOptions::max_children`].
+pub(crate) enum Elision {
+    Siblings(usize),
+    Descendants(usize),
+}
+
+impl Display for Elision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Elision::Siblings(n) => write!(f, "… {} more", n),
+            Elision::Descendants(1) => write!(f, "[+ 1 descendant]"),
+            Elision::Descendants(n) => write!(f, "[+ {} descendants]", n),
+        }
+    }
+}
+
+// ---------------------------------------------------------
+
+/// The lines `label` is drawn on, soft-wrapped to `room` columns if there is a limit.
+fn label_lines(label: &str, multiline: Multiline, room: Option<usize>) -> Vec<&str> {
+    if !multiline.splits(label) {
+        return match room {
+            Some(room) if multiline != Multiline::False => wrap(label, room),
+            _ => vec![label],
+        };
+    }
+    match room {
+        Some(room) => label.lines().flat_map(|line| wrap(line, room)).collect(),
+        None => label.lines().collect(),
+    }
+}
+
+/// Breaks `line` at whitespace into lines of at most `width` columns.
+///
+/// Words wider than `width` are broken wherever they overflow, and every line holds at least one
+/// character so that deep nodes still make progress.
+fn wrap(line: &str, width: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = line;
+    while rest.width() > width {
+        let mut used = 0;
+        let mut end = 0;
+        let mut space = None;
+        for (i, c) in rest.char_indices() {
+            if c.is_whitespace() {
+                space = Some(i);
+            }
+            used += c.width().unwrap_or(0);
+            if used > width {
+                break;
+            }
+            end = i + c.len_utf8();
+        }
+
+        let at = match space {
+            Some(i) if !rest[..i].trim_end().is_empty() => i,
+            _ => end.max(rest.chars().next().map_or(0, char::len_utf8)),
+        };
+        lines.push(rest[..at].trim_end());
+        rest = rest[at..].trim_start();
+    }
+    if !rest.is_empty() || lines.is_empty() {
+        lines.push(rest);
+    }
+    lines
+}
+
+/// Shortens `line` to `room` columns if there is a limit, ending it in `…` if anything was cut.
+fn fit(line: &str, room: Option<usize>) -> Cow<'_, str> {
+    match room {
+        Some(room) if line.width() > room => match room {
+            0 => Cow::Borrowed(""),
+            _ => Cow::Owned(format!("{}…", take_columns(line, room - 1))),
+        },
+        _ => Cow::Borrowed(line),
+    }
+}
+
+/// Writes the skip glyphs of every ancestor column.
+fn write_columns<D, W>(
+    w: &mut W,
+    frames: &[Frame<'_, D>],
+    width: usize,
+    depth_styles: &[Style],
+) -> fmt::Result
+where
+    D: Display,
+    W: Write,
+{
+    for (depth, frame) in frames.iter().enumerate() {
+        let (last, glyphs) = frame.column;
+        write_styled(
+            w,
+            Fit { prefix: glyphs.skip(last), width },
+            depth_style(depth_styles, depth),
+        )?;
+    }
+    Ok(())
+}
+
+/// The style of the glyphs in indentation column `depth`, cycling through `depth_styles`.
+fn depth_style(depth_styles: &[Style], depth: usize) -> Option<&Style> {
+    match depth_styles.len() {
+        0 => None,
+        n => depth_styles.get(depth % n),
+    }
+}
+
+fn write_styled<W>(w: &mut W, value: impl Display, style: Option<&Style>) -> fmt::Result
+where
+    W: Write,
+{
+    match style {
+        Some(style) => write!(w, "{}", style.apply_to(value)),
+        None => write!(w, "{}", value),
+    }
+}
+
+/// A `(glyph, indent)` prefix drawn in exactly `width` terminal columns.
+///
+/// A narrower prefix is padded with spaces. A wider one loses the start of its indent first, so
+/// the gap between glyph and label survives.
+struct Fit<'a> {
+    prefix: (&'a str, &'a str),
+    width: usize,
+}
+
+impl Display for Fit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Fit { prefix: (glyph, indent), width } = *self;
+        if glyph.width() + indent.width() == width {
+            return write!(f, "{}{}", glyph, indent);
+        }
+
+        let glyph = take_columns(glyph, width);
+        let room = width - glyph.width();
+        let indent = take_last_columns(indent, room);
+        write!(f, "{}{}{:pad$}", glyph, indent, "", pad = room - indent.width())
+    }
+}
+
+/// The longest prefix of `s` that fits in `width` columns.
+fn take_columns(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// The longest suffix of `s` that fits in `width` columns.
+fn take_last_columns(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices().rev() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[i + c.len_utf8()..];
+        }
+    }
+    s
+}
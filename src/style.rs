@@ -0,0 +1,180 @@
+use std::{env, fmt, str::FromStr};
+
+use console::Style;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use snafu::{ensure, Snafu};
+use strum::{Display as StrumDisplay, EnumString, EnumVariantNames};
+
+// ---------------------------------------------------------
+
+/// A [`console::Style`] spelled as a dotted string, such as `"red.bold"` or `"on_blue.dim"`.
+///
+/// Keeping the spelling around lets styles be compared, printed and exported to formats other
+/// than the terminal.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::TreeStyle;
+///
+/// let style: TreeStyle = "bold.blue".parse().unwrap();
+/// let styled = style.to_style().force_styling(true).apply_to("src");
+/// assert_eq!(styled.to_string(), "\u{1b}[34m\u{1b}[1msrc\u{1b}[0m");
+/// assert!("bold.blurple".parse::<TreeStyle>().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TreeStyle(String);
+
+impl TreeStyle {
+    /// Includes the `bright` and `on_bright` modifiers, which change the colors without being
+    /// colors themselves.
+    const ATTRIBUTES: [&'static str; 8] =
+        ["bold", "dim", "underlined", "blink", "reverse", "hidden", "bright", "on_bright"];
+    const COLORS: [&'static str; 8] =
+        ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+    /// The dotted spelling of the style.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The style to apply to terminal output.
+    pub fn to_style(&self) -> Style {
+        Style::from_dotted_str(&self.0)
+    }
+
+    /// The foreground color, either a name like `red` or an ANSI 256-color number.
+    pub fn foreground(&self) -> Option<&str> {
+        self.0.split('.').find(|part| Self::is_color(part))
+    }
+
+    /// The background color, without its `on_` prefix.
+    pub fn background(&self) -> Option<&str> {
+        self.0.split('.').find_map(|part| part.strip_prefix("on_").filter(|c| Self::is_color(c)))
+    }
+
+    /// Whether the style includes an attribute such as `bold`.
+    pub fn has_attribute(&self, attribute: &str) -> bool {
+        self.0.split('.').any(|part| part == attribute)
+    }
+
+    fn is_known(part: &str) -> bool {
+        Self::ATTRIBUTES.contains(&part) || Self::is_color(part.strip_prefix("on_").unwrap_or(part))
+    }
+
+    fn is_color(part: &str) -> bool {
+        Self::COLORS.contains(&part) || part.parse::<u8>().is_ok()
+    }
+}
+
+impl FromStr for TreeStyle {
+    type Err = StyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        for part in s.split('.') {
+            ensure!(Self::is_known(part), UnknownSnafu { part });
+        }
+        Ok(Self(s.to_owned()))
+    }
+}
+
+/// Serialized as its dotted spelling.
+impl Serialize for TreeStyle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for TreeStyle {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for TreeStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A color for a terminal color name that both Graphviz and CSS understand, the name itself or
+/// `#rrggbb` for an ANSI 256-color number.
+pub(crate) fn web_color(name: &str) -> Option<String> {
+    const NAMES: [&str; 8] =
+        ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    if NAMES.contains(&name) {
+        return Some(name.to_owned());
+    }
+    let number: u8 = name.parse().ok()?;
+    let (r, g, b) = match number {
+        0..=15 => return Some(NAMES[usize::from(number % 8)].to_owned()),
+        16..=231 => {
+            let i = usize::from(number - 16);
+            (CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6])
+        }
+        _ => {
+            let gray = 8 + (number - 232) * 10;
+            (gray, gray, gray)
+        }
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+#[derive(Debug, Snafu)]
+pub enum StyleError {
+    #[snafu(display("unknown style `{part}`, expected a color, `on_<color>` or an attribute"))]
+    Unknown { part: String },
+}
+
+// ---------------------------------------------------------
+
+/// When to emit ANSI colors.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    EnumString,
+    EnumVariantNames,
+    StrumDisplay,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves [`ColorChoice::Auto`] against the environment.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                // https://no-color.org: disabled when present and not empty.
+                let no_color = matches!(env::var_os("NO_COLOR"), Some(v) if !v.is_empty());
+                !no_color && atty::is(atty::Stream::Stdout)
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    pub(crate) fn is_auto(&self) -> bool {
+        *self == ColorChoice::Auto
+    }
+}
@@ -0,0 +1,406 @@
+//! Self-contained SVG drawings of trees, laid out top-down in the style of Reingold and Tilford.
+
+use std::fmt::{self, Display, Write};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{render::Elision, style::web_color, BinaryTree, Tree, TreeStyle};
+
+// ---------------------------------------------------------
+
+/// Sizes and looks of an SVG drawing, in pixels.
+///
+/// Labels are drawn in a monospace font and measured in terminal columns, each column being
+/// `0.6` times the font size wide.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{SvgOptions, Tree};
+///
+/// let options = SvgOptions { font_size: 20.0, ..Default::default() };
+/// let svg = Tree::new("foo").with_leaves(["bar", "baz"]).to_svg(&options);
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains("font-size=\"20\""));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    pub font_size: f64,
+    /// Horizontal space between neighbouring boxes.
+    pub node_gap: f64,
+    /// Vertical space between levels.
+    pub level_gap: f64,
+    /// Whether node styles color the labels and fill the boxes.
+    pub styles: bool,
+    /// Deepest level drawn, the root is level 0. Deeper boxes are summed up in a
+    /// `[+ 12 descendants]` box, like [`RenderOptions::max_depth`] does.
+    ///
+    /// [`RenderOptions::max_depth`]: crate::RenderOptions::max_depth
+    pub max_depth: Option<usize>,
+    /// Most children drawn per box, the rest are summed up in a `… 37 more` box.
+    pub max_children: Option<usize>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            font_size: 14.0,
+            node_gap: 16.0,
+            level_gap: 32.0,
+            styles: true,
+            max_depth: None,
+            max_children: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    fn padding(&self) -> f64 {
+        self.font_size / 2.0
+    }
+
+    fn line_height(&self) -> f64 {
+        self.font_size * 1.25
+    }
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Draws the tree as an SVG image of boxes joined by edges, each parent centered above its
+    /// children and every subtree packed as close to its left neighbour as it fits.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let mut shape = Shape::of_tree(self, options);
+        shape.elide(0, options);
+        Layout::new(shape, options).to_string()
+    }
+}
+
+impl<T> BinaryTree<T>
+where
+    T: Display,
+{
+    /// Draws the tree as an SVG image like [`Tree::to_svg`], an only child leaning towards its
+    /// side.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{BinaryTree, SvgOptions};
+    ///
+    /// let svg = BinaryTree::new(1).with_left(BinaryTree::new(2)).to_svg(&SvgOptions::default());
+    /// assert_eq!(svg.matches("<rect").count(), 2);
+    /// ```
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let mut shape = Shape::of_binary_tree(self, options);
+        shape.elide(0, options);
+        Layout::new(shape, options).to_string()
+    }
+}
+
+// ---------------------------------------------------------
+
+/// A box to draw and the boxes below it, before they are placed.
+struct Shape {
+    lines: Vec<String>,
+    style: Option<TreeStyle>,
+    width: f64,
+    height: f64,
+    /// `None` keeps the room of a missing binary tree child.
+    children: Vec<Option<Shape>>,
+}
+
+impl Shape {
+    fn new(label: &str, style: Option<TreeStyle>, options: &SvgOptions) -> Self {
+        let lines: Vec<String> = label.lines().map(str::to_owned).collect();
+        let lines = if lines.is_empty() { vec![String::new()] } else { lines };
+        let columns = lines.iter().map(|line| line.width()).max().unwrap_or_default();
+        // Terminal columns are far from overflowing a float.
+        #[allow(clippy::cast_precision_loss)]
+        let width = columns as f64 * options.font_size * 0.6 + 2.0 * options.padding();
+        #[allow(clippy::cast_precision_loss)]
+        let height = lines.len() as f64 * options.line_height() + options.padding();
+        Self { lines, style, width, height, children: Vec::new() }
+    }
+
+    fn of_tree<D>(tree: &Tree<D>, options: &SvgOptions) -> Self
+    where
+        D: Display,
+    {
+        let mut label = tree.root.to_string();
+        if let Some(annotation) = &tree.annotation {
+            label = format!("{}  {}", label, annotation);
+        }
+        let style = tree.style.clone().filter(|_| options.styles);
+        let mut shape = Self::new(&label, style, options);
+        shape.children =
+            tree.leaves.iter().map(|leaf| Some(Self::of_tree(leaf, options))).collect();
+        shape
+    }
+
+    fn of_binary_tree<T>(tree: &BinaryTree<T>, options: &SvgOptions) -> Self
+    where
+        T: Display,
+    {
+        let mut shape = Self::new(&tree.value.to_string(), None, options);
+        if tree.left.is_some() || tree.right.is_some() {
+            shape.children = [&tree.left, &tree.right]
+                .iter()
+                .map(|child| child.as_ref().map(|child| Self::of_binary_tree(child, options)))
+                .collect();
+        }
+        shape
+    }
+
+    /// Replaces the boxes beyond [`SvgOptions::max_depth`] and [`SvgOptions::max_children`]
+    /// with a box summing them up, `self` being at `depth`.
+    fn elide(&mut self, depth: usize, options: &SvgOptions) {
+        if matches!(options.max_depth, Some(max) if depth >= max) {
+            let count = self.count_descendants();
+            if count > 0 {
+                let elided = Elision::Descendants(count).to_string();
+                self.children = vec![Some(Self::new(&elided, None, options))];
+            }
+            return;
+        }
+
+        let len = self.children.iter().flatten().count();
+        if let Some(max) = options.max_children.filter(|&max| max < len) {
+            // Missing binary tree children past the last one shown go too.
+            let mut shown = 0;
+            self.children.retain(|child| {
+                shown += usize::from(child.is_some());
+                shown <= max
+            });
+            let elided = Elision::Siblings(len - max).to_string();
+            self.children.push(Some(Self::new(&elided, None, options)));
+        }
+        for child in self.children.iter_mut().flatten() {
+            child.elide(depth + 1, options);
+        }
+    }
+
+    fn count_descendants(&self) -> usize {
+        self.children.iter().flatten().map(|child| 1 + child.count_descendants()).sum()
+    }
+}
+
+/// The left and right edges of a subtree at each depth, relative to the center of its root.
+type Contour = Vec<(f64, f64)>;
+
+/// A placed box.
+struct Node {
+    lines: Vec<String>,
+    style: Option<TreeStyle>,
+    /// Center of the box, relative to its parent's until [`Layout::new`] is done.
+    x: f64,
+    depth: usize,
+    width: f64,
+    height: f64,
+    children: Vec<usize>,
+}
+
+/// Boxes placed in pre-order, with the top of each level.
+struct Layout<'a> {
+    nodes: Vec<Node>,
+    levels: Vec<f64>,
+    width: f64,
+    height: f64,
+    options: &'a SvgOptions,
+}
+
+impl<'a> Layout<'a> {
+    fn new(root: Shape, options: &'a SvgOptions) -> Self {
+        let mut layout =
+            Self { nodes: Vec::new(), levels: Vec::new(), width: 0.0, height: 0.0, options };
+        let contour = layout.place(root, 0);
+
+        // Levels are as tall as their tallest box.
+        let mut heights: Vec<f64> = vec![0.0; contour.len()];
+        for node in &layout.nodes {
+            heights[node.depth] = heights[node.depth].max(node.height);
+        }
+        let margin = options.node_gap;
+        let mut top = margin;
+        for height in &heights {
+            layout.levels.push(top);
+            top += height + options.level_gap;
+        }
+        layout.height = top - options.level_gap + margin;
+
+        // Turn the offsets into absolute positions, with the leftmost edge at the margin.
+        let left = contour.iter().map(|&(left, _)| left).fold(f64::INFINITY, f64::min);
+        let right = contour.iter().map(|&(_, right)| right).fold(f64::NEG_INFINITY, f64::max);
+        layout.width = right - left + 2.0 * margin;
+        layout.nodes[0].x = margin - left;
+        for index in 0..layout.nodes.len() {
+            let x = layout.nodes[index].x;
+            for child in layout.nodes[index].children.clone() {
+                layout.nodes[child].x += x;
+            }
+        }
+        layout
+    }
+
+    /// Places `shape` and its subtree, returning its contour. Children are pushed apart until
+    /// their contours are at least a gap apart at every depth, then the parent is centered
+    /// above the outermost ones.
+    fn place(&mut self, shape: Shape, depth: usize) -> Contour {
+        let index = self.nodes.len();
+        let half = shape.width / 2.0;
+        self.nodes.push(Node {
+            lines: shape.lines,
+            style: shape.style,
+            x: 0.0,
+            depth,
+            width: shape.width,
+            height: shape.height,
+            children: Vec::new(),
+        });
+
+        let gap = self.options.node_gap;
+        let mut merged: Contour = Vec::new();
+        let mut offsets = Vec::new();
+        for child in shape.children {
+            let (child, contour) = match child {
+                Some(child) => {
+                    let child_index = self.nodes.len();
+                    (Some(child_index), self.place(child, depth + 1))
+                }
+                // A missing binary tree child takes as much room as a gap.
+                None => (None, vec![(-gap / 2.0, gap / 2.0)]),
+            };
+            let offset = match merged.is_empty() {
+                true => 0.0,
+                false => merged
+                    .iter()
+                    .zip(&contour)
+                    .map(|(&(_, right), &(left, _))| right - left + gap)
+                    .fold(f64::NEG_INFINITY, f64::max),
+            };
+            for (level, &(left, right)) in contour.iter().enumerate() {
+                match merged.get_mut(level) {
+                    Some(edges) => edges.1 = right + offset,
+                    None => merged.push((left + offset, right + offset)),
+                }
+            }
+            offsets.push((child, offset));
+        }
+
+        let center = match (offsets.first(), offsets.last()) {
+            (Some(&(_, first)), Some(&(_, last))) => (first + last) / 2.0,
+            _ => 0.0,
+        };
+        for (child, offset) in offsets {
+            if let Some(child) = child {
+                self.nodes[child].x = offset - center;
+                self.nodes[index].children.push(child);
+            }
+        }
+        let below = merged.into_iter().map(|(left, right)| (left - center, right - center));
+        std::iter::once((-half, half)).chain(below).collect()
+    }
+}
+
+impl Display for Layout<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = self.options;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\" font-family=\"ui-monospace, monospace\" font-size=\"{2}\">",
+            Px(self.width),
+            Px(self.height),
+            Px(options.font_size)
+        )?;
+
+        writeln!(f, "<g fill=\"none\" stroke=\"#888\">")?;
+        for node in &self.nodes {
+            let bottom = self.levels[node.depth] + node.height;
+            for &child in &node.children {
+                let child = &self.nodes[child];
+                let middle = bottom + options.level_gap / 2.0;
+                writeln!(
+                    f,
+                    "<path d=\"M{} {}V{}H{}V{}\"/>",
+                    Px(node.x),
+                    Px(bottom),
+                    Px(middle),
+                    Px(child.x),
+                    Px(self.levels[child.depth])
+                )?;
+            }
+        }
+        writeln!(f, "</g>")?;
+
+        for node in &self.nodes {
+            let top = self.levels[node.depth];
+            let style = node.style.as_ref();
+            let fill = style.and_then(|s| s.background()).and_then(web_color);
+            writeln!(
+                f,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\" \
+                 stroke=\"#444\"/>",
+                Px(node.x - node.width / 2.0),
+                Px(top),
+                Px(node.width),
+                Px(node.height),
+                fill.as_deref().unwrap_or("white")
+            )?;
+
+            write!(f, "<text text-anchor=\"middle\"")?;
+            if let Some(color) = style.and_then(|s| s.foreground()).and_then(web_color) {
+                write!(f, " fill=\"{}\"", color)?;
+            }
+            if matches!(style, Some(style) if style.has_attribute("bold")) {
+                write!(f, " font-weight=\"bold\"")?;
+            }
+            write!(f, ">")?;
+            for (i, line) in node.lines.iter().enumerate() {
+                // Baselines sit a font size below the top of each line.
+                #[allow(clippy::cast_precision_loss)]
+                let y = top + options.padding() / 2.0 + i as f64 * options.line_height();
+                write!(
+                    f,
+                    "<tspan x=\"{}\" y=\"{}\" xml:space=\"preserve\">{}</tspan>",
+                    Px(node.x),
+                    Px(y + options.font_size),
+                    Escaped(line)
+                )?;
+            }
+            writeln!(f, "</text>")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+/// A coordinate, rounded to a tenth of a pixel.
+struct Px(f64);
+
+impl Display for Px {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rounded = (self.0 * 10.0).round() / 10.0;
+        // Avoids `-0`.
+        write!(f, "{}", rounded + 0.0)
+    }
+}
+
+/// Text inside an SVG element.
+struct Escaped<'a>(&'a str);
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
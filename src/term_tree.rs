@@ -1,23 +1,216 @@
 //! Code derived from [termtree](https://github.com/rust-cli/termtree/blob/main/src/lib.rs)
 #![warn(rustdoc::missing_doc_code_examples)]
 
-use std::{collections::VecDeque, fmt::Display, rc::Rc};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+    io,
+    num::ParseIntError,
+    str::FromStr,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use snafu::{ensure, ResultExt, Snafu};
+use strum::{Display as StrumDisplay, EnumString, EnumVariantNames};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{render, ColorChoice, TreeStyle};
 
 // ---------------------------------------------------------
 
+/// A tree of labels, displayed with branch glyphs like `tree(1)`.
+///
+/// Serializes to JSON with the node settings and rendering options that differ from the
+/// defaults, so that a saved tree displays the same once it's read back:
+///
+/// ```json
+/// {
+///     "root": "src",
+///     "leaves": [
+///         { "root": "lib.rs", "annotation": "10K" },
+///         { "root": "bin", "style": "bold.blue", "leaves": [{ "root": "main.rs" }] }
+///     ],
+///     "options": { "color": "never", "max_depth": 3 }
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
 pub struct Tree<D>
 where
     D: Display,
 {
     pub root: D,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub leaves: Vec<Tree<D>>,
-    multiline: bool,
-    glyphs: GlyphPalette,
+    #[serde(default, skip_serializing_if = "Multiline::is_auto")]
+    pub(crate) multiline: Multiline,
+    /// Overrides [`RenderOptions::glyphs`] for this node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) glyphs: Option<GlyphPalette>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) style: Option<TreeStyle>,
+    /// Right-aligned metadata, such as a size, drawn after the label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) annotation: Option<String>,
+    /// Only consulted on the node that gets displayed, `None` means the defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) options: Option<Box<RenderOptions>>,
 }
 
 pub trait TreeSetter {
     fn set_multiline(&mut self, is_multiline: Multiline) -> &mut Self;
     fn set_glyphs(&mut self, glyphs: GlyphPalette) -> &mut Self;
+    fn set_palette(&mut self, glyphs: GlyphPalette) -> &mut Self;
+    fn set_options(&mut self, options: RenderOptions) -> &mut Self;
+    fn set_style(&mut self, style: TreeStyle) -> &mut Self;
+    fn set_annotation(&mut self, annotation: impl Into<String>) -> &mut Self;
+}
+
+/// Options that apply to the whole tree when it is displayed.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{GlyphPalette, RenderOptions, Tree};
+///
+/// let options = RenderOptions { glyphs: GlyphPalette::ascii(), ..Default::default() };
+/// let tree = Tree::new("foo").with_leaves(["bar", "baz"]).with_options(options);
+/// assert_eq!(tree.to_string(), "foo\n|-- bar\n`-- baz\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// Palette for every node without its own [`Tree::with_glyphs`] override.
+    ///
+    /// Also sets the width of each indentation column, overrides are padded or cut to fit.
+    #[serde(skip_serializing_if = "GlyphPalette::is_default")]
+    pub glyphs: GlyphPalette,
+    /// Whether node and depth styles are rendered at all.
+    #[serde(skip_serializing_if = "ColorChoice::is_auto")]
+    pub color: ColorChoice,
+    /// Styles for the branch glyphs, cycled through by depth.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depth_styles: Vec<TreeStyle>,
+    /// Deepest level shown, the root is level 0. Deeper nodes are summed up as
+    /// `[+ 12 descendants]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    /// Most children shown per node, the rest are summed up as `… 37 more`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_children: Option<usize>,
+    /// Soft-wraps labels at whitespace so that lines, indentation and annotations included, fit
+    /// this width.
+    ///
+    /// Continuation lines are drawn behind the skip glyphs, like multi-line labels. Labels with
+    /// [`Multiline::False`] are left alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap: Option<LineWidth>,
+    /// Cuts every line, indentation and annotations included, to this width and ends the cut
+    /// ones in `…`.
+    ///
+    /// Applied after [`RenderOptions::wrap`], deeper nodes get less room for their labels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncate: Option<LineWidth>,
+}
+
+/// Width of the rendered lines, in terminal columns.
+///
+/// Parsed from a number of columns or `auto`, and serialized as a number or `"auto"`. A width
+/// of `0` is rejected, as nothing would fit in it.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{LineWidth, RenderOptions, Tree};
+///
+/// let options = RenderOptions { wrap: Some("12".parse().unwrap()), ..Default::default() };
+/// let tree = Tree::new("foo").with_leaves(["a rather long label"]).with_options(options);
+/// assert_eq!(tree.to_string(), "foo\n└── a rather\n    long\n    label\n");
+/// assert_eq!("auto".parse::<LineWidth>().unwrap(), LineWidth::Terminal);
+/// assert!("0".parse::<LineWidth>().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineWidth {
+    Columns(usize),
+    /// The width of the terminal on stdout, or no limit when stdout isn't a terminal.
+    Terminal,
+}
+
+impl LineWidth {
+    /// Resolves the width, `None` meaning unlimited.
+    pub fn columns(self) -> Option<usize> {
+        match self {
+            LineWidth::Columns(columns) => Some(columns),
+            LineWidth::Terminal => {
+                console::Term::stdout().size_checked().map(|(_, columns)| columns.into())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum LineWidthError {
+    #[snafu(display("expected a number of columns or `auto`: {source}"))]
+    Invalid { source: ParseIntError },
+    #[snafu(display("a line width can't be 0"))]
+    Zero,
+}
+
+impl FromStr for LineWidth {
+    type Err = LineWidthError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(LineWidth::Terminal),
+            _ => match s.parse().context(InvalidSnafu)? {
+                0 => ZeroSnafu.fail(),
+                columns => Ok(LineWidth::Columns(columns)),
+            },
+        }
+    }
+}
+
+impl Serialize for LineWidth {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            LineWidth::Columns(columns) => columns.serialize(serializer),
+            LineWidth::Terminal => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LineWidth {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Columns(usize),
+            Named(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Columns(0) => Err(de::Error::custom(LineWidthError::Zero)),
+            Repr::Columns(columns) => Ok(LineWidth::Columns(columns)),
+            Repr::Named(name) => name.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+impl Display for LineWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineWidth::Columns(columns) => write!(f, "{}", columns),
+            LineWidth::Terminal => f.write_str("auto"),
+        }
+    }
+}
+
+lazy_static! {
+    static ref DEFAULT_OPTIONS: RenderOptions = RenderOptions::default();
 }
 
 // ---------------------------------------------------------
@@ -27,7 +220,59 @@ where
     D: Display,
 {
     pub fn new(root: D) -> Self {
-        Self { root, leaves: Vec::new(), multiline: false, glyphs: GlyphPalette::new() }
+        Self {
+            root,
+            leaves: Vec::new(),
+            multiline: Multiline::Auto,
+            glyphs: None,
+            style: None,
+            annotation: None,
+            options: None,
+        }
+    }
+
+    pub(crate) fn count_descendants(&self) -> usize {
+        self.leaves.iter().map(|leaf| 1 + leaf.count_descendants()).sum()
+    }
+
+    pub(crate) fn has_annotations(&self) -> bool {
+        self.annotation.is_some() || self.leaves.iter().any(Tree::has_annotations)
+    }
+
+    /// Streams the rendered tree to `writer`, exactly as [`Display`] would format it.
+    ///
+    /// Only the ancestors of the current node are kept in memory, so huge trees don't need to be
+    /// rendered to a `String` first. Wrap unbuffered writers such as files in a
+    /// [`std::io::BufWriter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let mut buf = Vec::new();
+    /// Tree::new("foo").with_leaves(["bar"]).write_to(&mut buf).unwrap();
+    /// assert_eq!(buf, "foo\n└── bar\n".as_bytes());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `writer` fails, or if formatting a label fails.
+    pub fn write_to<W>(&self, writer: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        render::write_to(self, writer)
+    }
+
+    /// Options used when this tree is displayed.
+    pub fn options(&self) -> &RenderOptions {
+        self.options.as_deref().unwrap_or(&DEFAULT_OPTIONS)
+    }
+
+    /// Mutable access to the options used when this tree is displayed.
+    pub fn options_mut(&mut self) -> &mut RenderOptions {
+        self.options.get_or_insert_with(Default::default)
     }
 
     pub fn push(&mut self, leaf: impl Into<Tree<D>>) -> &mut Self {
@@ -36,9 +281,44 @@ where
         self
     }
 
-    /// Customize how this mode is rendered
+    /// Customize how this node is rendered, overriding the tree-wide palette.
     pub fn with_glyphs(mut self, glyphs: GlyphPalette) -> Self {
-        self.glyphs = glyphs;
+        self.glyphs = Some(glyphs);
+        self
+    }
+
+    /// Render every node of the tree with `glyphs`, unless it has its own override.
+    pub fn with_palette(mut self, glyphs: GlyphPalette) -> Self {
+        self.options_mut().glyphs = glyphs;
+        self
+    }
+
+    /// Style for the label of this node.
+    pub fn with_style(mut self, style: TreeStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Metadata such as a size or a date, drawn in a right-aligned column after the labels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new("src").with_annotation("12K").with_leaves([
+    ///     Tree::new("lib.rs").with_annotation("10K"),
+    ///     Tree::new("main.rs").with_annotation("900"),
+    /// ]);
+    /// assert_eq!(tree.to_string(), "src          12K\n├── lib.rs   10K\n└── main.rs  900\n");
+    /// ```
+    pub fn with_annotation(mut self, annotation: impl Into<String>) -> Self {
+        self.annotation = Some(annotation.into());
+        self
+    }
+
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.options = Some(Box::new(options));
         self
     }
 
@@ -47,9 +327,11 @@ where
         self
     }
 
-    // Ensure all lines for `root` are indented.
+    /// Overrides whether the lines of `root` are drawn as separate, indented lines.
+    ///
+    /// Labels containing newlines are detected without it.
     pub fn with_multiline(mut self, is_multiline: Multiline) -> Self {
-        self.multiline = matches!(is_multiline, Multiline::True);
+        self.multiline = is_multiline;
         self
     }
 }
@@ -58,16 +340,36 @@ impl<D> TreeSetter for Tree<D>
 where
     D: Display,
 {
-    /// Ensure all lines for `root` are indented.
+    /// Overrides whether the lines of `root` are drawn as separate, indented lines.
     fn set_multiline(&mut self, is_multiline: Multiline) -> &mut Self {
-        self.multiline = matches!(is_multiline, Multiline::True);
-        assert!(self.multiline);
+        self.multiline = is_multiline;
         self
     }
 
-    /// Customize the reneding of this node.
+    /// Customize the rendering of this node.
     fn set_glyphs(&mut self, glyphs: GlyphPalette) -> &mut Self {
-        self.glyphs = glyphs;
+        self.glyphs = Some(glyphs);
+        self
+    }
+
+    /// Customize the rendering of every node.
+    fn set_palette(&mut self, glyphs: GlyphPalette) -> &mut Self {
+        self.options_mut().glyphs = glyphs;
+        self
+    }
+
+    fn set_options(&mut self, options: RenderOptions) -> &mut Self {
+        self.options = Some(Box::new(options));
+        self
+    }
+
+    fn set_style(&mut self, style: TreeStyle) -> &mut Self {
+        self.style = Some(style);
+        self
+    }
+
+    fn set_annotation(&mut self, annotation: impl Into<String>) -> &mut Self {
+        self.annotation = Some(annotation.into());
         self
     }
 }
@@ -88,7 +390,7 @@ where
     D: Display,
 {
     fn extend<T: IntoIterator<Item = D>>(&mut self, iter: T) {
-        self.leaves.extend(iter.into_iter().map(Into::into))
+        self.leaves.extend(iter.into_iter().map(Into::into));
     }
 }
 
@@ -97,7 +399,7 @@ where
     D: Display,
 {
     fn extend<T: IntoIterator<Item = Tree<D>>>(&mut self, iter: T) {
-        self.leaves.extend(iter)
+        self.leaves.extend(iter);
     }
 }
 
@@ -107,101 +409,34 @@ impl<D> Display for Tree<D>
 where
     D: Display,
 {
-    fn fmt<'t>(&'t self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.root)?;
-        let mut queue = DisplayQueue::<'t, D>::new();
-        let no_space: Rc<Vec<bool>> = Rc::new(Vec::new());
-        enqueue_leaves(&mut queue, self, no_space);
-
-        // Loop while removing the first element and return it,
-        // or break the loop if the deque is empty if `None`.
-        while let Some((last, leaf, spaces)) = queue.pop_front() {
-            let mut prefix: (&str, &str) = (
-                if last { leaf.glyphs.last_item } else { leaf.glyphs.middle_item },
-                leaf.glyphs.item_indent,
-            );
-
-            if Multiline::True.from_tree(leaf) {
-                // if leaf.multiline {
-                let rest_prefix: (&str, &str) = (
-                    if last { leaf.glyphs.last_skip } else { leaf.glyphs.middle_skip },
-                    leaf.glyphs.skip_indent,
-                );
-                debug_assert_eq!(prefix.0.chars().count(), rest_prefix.0.chars().count());
-                debug_assert_eq!(prefix.1.chars().count(), rest_prefix.1.chars().count());
-
-                let root: String = leaf.root.to_string();
-                for line in root.lines() {
-                    // Print single line
-                    for s in spaces.as_slice() {
-                        if *s {
-                            write!(f, "{}{}", self.glyphs.last_skip, self.glyphs.skip_indent)?;
-                        } else {
-                            write!(f, "{}{}", self.glyphs.middle_skip, self.glyphs.skip_indent)?;
-                        }
-                    }
-                    writeln!(f, "{}{}{}", prefix.0, prefix.1, line)?;
-                    prefix = rest_prefix;
-                }
-            } else {
-                // Print single line
-                for s in spaces.as_slice() {
-                    if *s {
-                        write!(f, "{}{}", self.glyphs.last_skip, self.glyphs.skip_indent)?;
-                    } else {
-                        write!(f, "{}{}", self.glyphs.middle_skip, self.glyphs.skip_indent)?;
-                    }
-                }
-                writeln!(f, "{}{}{}", prefix.0, prefix.1, leaf.root)?;
-            };
-
-            // Recursion
-            if !leaf.leaves.is_empty() {
-                let s: &Vec<bool> = &spaces;
-                let mut child_spaces = s.clone();
-                child_spaces.push(last);
-                let child_spaces: Rc<Vec<bool>> = Rc::new(child_spaces);
-                enqueue_leaves(&mut queue, leaf, child_spaces);
-            }
-        }
-
-        Ok(())
-    }
-}
-
-// ---------------------------------------------------------
-
-type DisplayQueue<'t, D> = VecDeque<(bool, &'t Tree<D>, Rc<Vec<bool>>)>;
-
-fn enqueue_leaves<'t, D>(
-    queue: &mut DisplayQueue<'t, D>,
-    parent: &'t Tree<D>,
-    spaces: Rc<Vec<bool>>,
-) where
-    D: Display,
-{
-    for (i, leaf) in parent.leaves.iter().rev().enumerate() {
-        let last = i == 0;
-        queue.push_front((last, leaf, spaces.clone()));
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        render::render(self, f)
     }
 }
 
 // ---------------------------------------------------------
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Glyphs drawn in front of every node, in front of its continuation lines and in the columns of
+/// its descendants.
+///
+/// Widths are measured in terminal columns, so wide or emoji glyphs are fine as long as every
+/// `*_item` + `item_indent` and `*_skip` + `skip_indent` pair has the same width, see
+/// [`GlyphPalette::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GlyphPalette {
-    pub middle_item: &'static str,
-    pub last_item: &'static str,
-    pub item_indent: &'static str,
+    pub middle_item: Cow<'static, str>,
+    pub last_item: Cow<'static, str>,
+    pub item_indent: Cow<'static, str>,
 
-    pub middle_skip: &'static str,
-    pub last_skip: &'static str,
-    pub skip_indent: &'static str,
+    pub middle_skip: Cow<'static, str>,
+    pub last_skip: Cow<'static, str>,
+    pub skip_indent: Cow<'static, str>,
 }
 
 /// The advantage of implementing or deriving Default is that your type can now be used where a
-/// Default implementation is required, most prominently, any of the *or_default functions in the
-/// standard library.
+/// Default implementation is required, most prominently, any of the `*or_default` functions in
+/// the standard library.
 impl Default for GlyphPalette {
     fn default() -> Self {
         Self::new()
@@ -209,38 +444,236 @@ impl Default for GlyphPalette {
 }
 
 impl GlyphPalette {
+    /// Number of columns taken by each level of indentation.
+    pub fn width(&self) -> usize {
+        self.middle_item.width() + self.item_indent.width()
+    }
+
+    /// Prefix in front of the first line of a node.
+    pub(crate) fn item(&self, last: bool) -> (&str, &str) {
+        (if last { &self.last_item } else { &self.middle_item }, &self.item_indent)
+    }
+
+    /// Prefix in front of continuation lines, and in the column of the node's descendants.
+    pub(crate) fn skip(&self, last: bool) -> (&str, &str) {
+        (if last { &self.last_skip } else { &self.middle_skip }, &self.skip_indent)
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Box-drawing glyphs, e.g. `├── ` and `└── `.
     pub fn new() -> Self {
         Self {
-            middle_item: "├",
-            last_item: "└",
-            item_indent: "── ",
+            middle_item: "├".into(),
+            last_item: "└".into(),
+            item_indent: "── ".into(),
+
+            middle_skip: "│".into(),
+            last_skip: " ".into(),
+            skip_indent: "   ".into(),
+        }
+    }
+
+    /// Plain ASCII glyphs, e.g. `|-- ` and `` `-- ``, for logs and consoles without Unicode.
+    pub fn ascii() -> Self {
+        Self {
+            middle_item: "|".into(),
+            last_item: "`".into(),
+            item_indent: "-- ".into(),
+            middle_skip: "|".into(),
+            ..Self::new()
+        }
+    }
+
+    /// Box-drawing glyphs with a rounded last item, e.g. `╰── `.
+    pub fn rounded() -> Self {
+        Self { last_item: "╰".into(), ..Self::new() }
+    }
+
+    /// Heavy box-drawing glyphs, e.g. `┣━━ ` and `┗━━ `.
+    pub fn heavy() -> Self {
+        Self {
+            middle_item: "┣".into(),
+            last_item: "┗".into(),
+            item_indent: "━━ ".into(),
+            middle_skip: "┃".into(),
+            ..Self::new()
+        }
+    }
+
+    /// Double-line box-drawing glyphs, e.g. `╠══ ` and `╚══ `.
+    pub fn double() -> Self {
+        Self {
+            middle_item: "╠".into(),
+            last_item: "╚".into(),
+            item_indent: "══ ".into(),
+            middle_skip: "║".into(),
+            ..Self::new()
+        }
+    }
+
+    /// No branches at all, nodes are only indented by their depth.
+    pub fn indent_only() -> Self {
+        Self {
+            middle_item: " ".into(),
+            last_item: " ".into(),
+            item_indent: "   ".into(),
+            middle_skip: " ".into(),
+            ..Self::new()
+        }
+    }
 
-            middle_skip: "│",
-            last_skip: " ",
-            skip_indent: "   ",
+    /// Resizes each level of indentation to `width` columns, e.g. `├─ ` for a width of 3.
+    ///
+    /// The item indent is filled with the first glyph of the current one and ends in a space,
+    /// as long as `width` leaves room for it next to the branch glyph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{GlyphPalette, Tree};
+    ///
+    /// let glyphs = GlyphPalette::new().with_indent(2);
+    /// let tree = Tree::new("foo").with_leaves(["bar"]).with_palette(glyphs);
+    /// assert_eq!(tree.to_string(), "foo\n└ bar\n");
+    /// ```
+    pub fn with_indent(mut self, width: usize) -> Self {
+        let fill = self.item_indent.chars().next().filter(|c| !c.is_whitespace()).unwrap_or(' ');
+        let item_room = width.saturating_sub(self.middle_item.width().max(self.last_item.width()));
+        let mut item_indent: String = fill.to_string().repeat(item_room.saturating_sub(1));
+        if item_room > 0 {
+            item_indent.push(' ');
+        }
+        let skip_room = width.saturating_sub(self.middle_skip.width().max(self.last_skip.width()));
+
+        self.item_indent = item_indent.into();
+        self.skip_indent = " ".repeat(skip_room).into();
+        self
+    }
+
+    /// Checks that every prefix takes as many terminal columns as `middle_item` + `item_indent`.
+    ///
+    /// Misaligned palettes still render, but their prefixes get padded or cut to fit.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`GlyphError::Misaligned`] for the first prefix of the wrong
+    /// width.
+    pub fn validate(&self) -> Result<(), GlyphError> {
+        let expected = self.width();
+        let prefixes = [
+            ("last_item", self.item(true)),
+            ("middle_skip", self.skip(false)),
+            ("last_skip", self.skip(true)),
+        ];
+        for (name, (glyph, indent)) in prefixes {
+            let found = glyph.width() + indent.width();
+            ensure!(found == expected, MisalignedSnafu { name, expected, found });
+        }
+        Ok(())
+    }
+
+    /// Reads a palette from a JSON theme like `{ "middle_item": "┝", "item_indent": "━ " }`.
+    ///
+    /// Missing glyphs are taken from [`GlyphPalette::new`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `json` isn't a valid theme, or if the resulting
+    /// palette fails [`GlyphPalette::validate`].
+    pub fn from_theme(json: &str) -> Result<Self, GlyphError> {
+        // Only an object is a theme, not an array of glyphs in field order.
+        let theme: serde_json::Map<_, _> = serde_json::from_str(json).context(ThemeSnafu)?;
+        let glyphs = Self::deserialize(serde_json::Value::Object(theme)).context(ThemeSnafu)?;
+        glyphs.validate()?;
+        Ok(glyphs)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum GlyphError {
+    #[snafu(display(
+        "`{name}` prefix is {found} columns wide, but `middle_item` prefix is {expected}"
+    ))]
+    Misaligned { name: &'static str, expected: usize, found: usize },
+    #[snafu(display("invalid glyph theme: {source}"))]
+    Theme { source: serde_json::Error },
+}
+
+impl From<GlyphPreset> for GlyphPalette {
+    fn from(preset: GlyphPreset) -> Self {
+        match preset {
+            GlyphPreset::Box => Self::new(),
+            GlyphPreset::Ascii => Self::ascii(),
+            GlyphPreset::Rounded => Self::rounded(),
+            GlyphPreset::Heavy => Self::heavy(),
+            GlyphPreset::Double => Self::double(),
+            GlyphPreset::Indent => Self::indent_only(),
         }
     }
 }
 
+/// Named [`GlyphPalette`] presets, parsed from and displayed as their lowercase name.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{GlyphPalette, GlyphPreset};
+///
+/// let preset: GlyphPreset = "ascii".parse().unwrap();
+/// assert_eq!(GlyphPalette::from(preset), GlyphPalette::ascii());
+/// ```
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, EnumString, EnumVariantNames, StrumDisplay,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum GlyphPreset {
+    #[default]
+    Box,
+    Ascii,
+    Rounded,
+    Heavy,
+    Double,
+    Indent,
+}
+
 // ---------------------------------------------------------
 
+/// Whether the lines of a label are drawn as separate, indented lines.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::{Multiline, Tree};
+///
+/// let tree = Tree::new("foo").with_leaves(["hello\nworld", "bar"]);
+/// assert_eq!(tree.to_string(), "foo\n├── hello\n│   world\n└── bar\n");
+///
+/// let tree = Tree::new("foo").with_leaves([Tree::new("a\nb").with_multiline(Multiline::False)]);
+/// assert_eq!(tree.to_string(), "foo\n└── a\nb\n");
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Multiline {
+    /// Only labels containing a newline.
+    #[default]
+    Auto,
+    /// Never, the label is written as is.
     False,
+    /// Always. A label without newlines is a single line either way.
     True,
 }
 
-impl Default for Multiline {
-    fn default() -> Self {
-        Self::False
+impl Multiline {
+    /// Whether `label` gets split into lines.
+    pub(crate) fn splits(self, label: &str) -> bool {
+        self != Multiline::False && label.contains('\n')
     }
-}
 
-impl Multiline {
-    fn from_tree<D>(&self, leaf: &Tree<D>) -> bool
-    where
-        D: Display,
-    {
-        matches!((self, leaf.multiline), (Multiline::True, true))
+    fn is_auto(&self) -> bool {
+        *self == Multiline::Auto
     }
 }
 
@@ -58,13 +58,253 @@ fn render_tree_with_mutltiline_leaves() {
     );
 }
 
+#[test]
+fn render_tree_with_detected_multiline_leaves() {
+    let mut tree = Tree::new("foo").with_leaves([
+        Tree::new("hello\nworld").with_leaves(["bar"]),
+        Tree::new("goodbye\nworld"),
+        Tree::new("raw\nlabel"),
+    ]);
+    tree.leaves[2].set_multiline(Multiline::False);
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+├── hello
+│   world
+│   └── bar
+├── goodbye
+│   world
+└── raw
+label
+"#
+    );
+}
+
+#[test]
+fn render_tree_with_ascii_glyphs() {
+    let glyphs = GlyphPalette::ascii();
+    let tree = Tree::new("foo").with_glyphs(glyphs.clone()).with_leaves([
+        Tree::new("bar")
+            .with_glyphs(glyphs.clone())
+            .with_leaves([Tree::new("baz").with_glyphs(glyphs.clone())]),
+        Tree::new("qux").with_glyphs(glyphs),
+    ]);
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+|-- bar
+|   `-- baz
+`-- qux
+"#
+    );
+}
+
+#[test]
+fn render_tree_with_palette_from_root() {
+    let tree = Tree::new("foo")
+        .with_leaves([Tree::new("bar").with_leaves(["baz"]), Tree::new("qux")])
+        .with_palette(GlyphPalette::heavy());
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+┣━━ bar
+┃   ┗━━ baz
+┗━━ qux
+"#
+    );
+}
+
+#[test]
+fn render_tree_with_glyph_overrides_aligned() {
+    let narrow = GlyphPalette::ascii().with_indent(3);
+    let wide = GlyphPalette::new().with_indent(8);
+    let tree = Tree::new("foo").with_leaves([
+        Tree::new("bar").with_glyphs(narrow).with_leaves(["baz"]),
+        Tree::new("qux").with_glyphs(wide).with_leaves(["quux"]),
+    ]);
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+|-  bar
+|   └── baz
+└── qux
+    └── quux
+"#
+    );
+}
+
+#[test]
+fn render_tree_with_indent_widths() {
+    let tree =
+        Tree::new("foo").with_leaves([Tree::new("bar").with_leaves(["baz"]), Tree::new("qux")]);
+    let tree = tree.with_palette(GlyphPalette::new().with_indent(2));
+    assert_eq!(format!("{}", tree), "foo\n├ bar\n│ └ baz\n└ qux\n");
+
+    let tree = tree.with_palette(GlyphPalette::ascii().with_indent(8));
+    assert_eq!(format!("{}", tree), "foo\n|------ bar\n|       `------ baz\n`------ qux\n");
+}
+
+#[test]
+fn render_tree_with_wide_glyphs() {
+    let glyphs = GlyphPalette {
+        middle_item: "🌿".into(),
+        last_item: "🍂".into(),
+        item_indent: " ".into(),
+        middle_skip: "│".into(),
+        last_skip: " ".into(),
+        skip_indent: "  ".into(),
+    };
+    assert!(glyphs.validate().is_ok());
+    let tree =
+        Tree::new("foo").with_leaves([Tree::new("bar").with_leaves(["baz"]), Tree::new("qux")]);
+    assert_eq!(
+        format!("{}", tree.with_palette(glyphs)),
+        r#"foo
+🌿 bar
+│  🍂 baz
+🍂 qux
+"#
+    );
+}
+
+#[test]
+fn glyph_theme_validation() {
+    let glyphs = GlyphPalette::from_theme(r#"{ "middle_item": "+", "last_item": "\\" }"#).unwrap();
+    assert_eq!(
+        glyphs,
+        GlyphPalette { middle_item: "+".into(), last_item: "\\".into(), ..GlyphPalette::new() }
+    );
+
+    let err = GlyphPalette::from_theme(r#"{ "item_indent": "- " }"#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`middle_skip` prefix is 4 columns wide, but `middle_item` prefix is 3"
+    );
+    assert!(GlyphPalette::from_theme("[]").is_err());
+    assert!(GlyphPalette::from_theme(r#"["+", "\\"]"#).is_err());
+    assert!(GlyphPalette::from_theme("null").is_err());
+}
+
+#[test]
+fn render_tree_with_styles() {
+    let red: TreeStyle = "red".parse().unwrap();
+    let tree = Tree::new("foo")
+        .with_leaves([Tree::new("bar").with_style("bold".parse().unwrap()).with_leaves(["baz"])])
+        .with_options(RenderOptions {
+            color: ColorChoice::Always,
+            depth_styles: vec![red, "green".parse().unwrap()],
+            ..Default::default()
+        });
+    assert_eq!(
+        format!("{}", tree),
+        "foo\n\
+         \u{1b}[31m└── \u{1b}[0m\u{1b}[1mbar\u{1b}[0m\n\
+         \u{1b}[31m    \u{1b}[0m\u{1b}[32m└── \u{1b}[0mbaz\n"
+    );
+
+    let mut tree = tree;
+    tree.options_mut().color = ColorChoice::Never;
+    assert_eq!(format!("{}", tree), "foo\n└── bar\n    └── baz\n");
+}
+
+#[test]
+fn render_tree_with_max_depth_and_children() {
+    let tree = Tree::new("foo").with_leaves([
+        Tree::new("bar").with_leaves([Tree::new("baz").with_leaves(["qux"]), Tree::new("quux")]),
+        Tree::new("a"),
+        Tree::new("b"),
+        Tree::new("c"),
+    ]);
+    let mut tree = tree.with_options(RenderOptions {
+        max_depth: Some(1),
+        max_children: Some(2),
+        ..Default::default()
+    });
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+├── bar
+│   └── [+ 3 descendants]
+├── a
+└── … 2 more
+"#
+    );
+
+    tree.options_mut().max_depth = Some(0);
+    assert_eq!(format!("{}", tree), "foo\n└── [+ 7 descendants]\n");
+
+    tree.options_mut().max_depth = None;
+    tree.options_mut().max_children = Some(1);
+    assert_eq!(
+        format!("{}", tree),
+        r#"foo
+├── bar
+│   ├── baz
+│   │   └── qux
+│   └── … 1 more
+└── … 3 more
+"#
+    );
+}
+
+#[test]
+fn write_tree_to_io_matches_display() {
+    let tree = Tree::new("foo").with_leaves([
+        Tree::new("bar").with_leaves([Tree::new("hello\nworld").with_multiline(Multiline::True)]),
+        Tree::new("baz").with_glyphs(GlyphPalette::double()).with_leaves(["qux", "quux"]),
+    ]);
+    let mut buf = Vec::new();
+    tree.write_to(&mut buf).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), tree.to_string());
+}
+
+#[test]
+fn write_deep_and_wide_tree() {
+    // A path of 1000 nodes, each with a sibling.
+    let mut tree = Tree::new(0);
+    for i in 1..1000 {
+        tree = Tree::new(i).with_leaves([tree, Tree::new(-i)]);
+    }
+    let mut buf = Vec::new();
+    tree.write_to(&mut buf).unwrap();
+    let rendered = String::from_utf8(buf).unwrap();
+    assert_eq!(rendered.lines().count(), 1999);
+    assert!(rendered.ends_with("\n└── -999\n"));
+    assert!(rendered.contains(&format!("\n{}├── 0\n", "│   ".repeat(998))));
+}
+
+#[test]
+fn tree_style_parts() {
+    let style: TreeStyle = "bold.on_white.196".parse().unwrap();
+    assert_eq!(style.foreground(), Some("196"));
+    assert_eq!(style.background(), Some("white"));
+    assert!(style.has_attribute("bold"));
+    let style: TreeStyle = "bright.red.on_bright.on_blue".parse().unwrap();
+    assert_eq!(style.foreground(), Some("red"));
+    assert_eq!(style.background(), Some("blue"));
+    assert!(style.has_attribute("bright"));
+    assert_eq!("bright".parse::<TreeStyle>().unwrap().foreground(), None);
+    assert_eq!(
+        "bold.sparkly".parse::<TreeStyle>().unwrap_err().to_string(),
+        "unknown style `sparkly`, expected a color, `on_<color>` or an attribute"
+    );
+}
+
+#[test]
+fn glyph_presets_from_name() {
+    assert_eq!("box".parse::<GlyphPreset>().unwrap(), GlyphPreset::default());
+    assert_eq!(GlyphPalette::from("heavy".parse::<GlyphPreset>().unwrap()), GlyphPalette::heavy());
+    assert_eq!(GlyphPreset::Double.to_string(), "double");
+    assert!("fancy".parse::<GlyphPreset>().is_err());
+}
+
 // ---------------------------------------------------------
 // https://github.com/BurntSushi/quickcheck/blob/master/examples/reverse.rs
 
 fn reverse<T: Clone>(xs: &[T]) -> Vec<T> {
     let mut rev = vec![];
     for x in xs {
-        rev.insert(0, x.clone())
+        rev.insert(0, x.clone());
     }
     rev
 }
@@ -93,3 +333,913 @@ fn test_proptest() {
         println!("        = {}", str_val.current());
     }
 }
+
+#[test]
+fn wrap_labels_to_width() {
+    let options = RenderOptions { wrap: Some(LineWidth::Columns(17)), ..Default::default() };
+    let tree = Tree::new("root label that wraps")
+        .with_leaves([
+            Tree::new("short").with_leaves(["the quick brown fox", "supercalifragilistic"]),
+            Tree::new("two\nlines of text"),
+        ])
+        .with_options(options);
+    assert_eq!(
+        tree.to_string(),
+        r#"root label that
+wraps
+├── short
+│   ├── the quick
+│   │   brown fox
+│   └── supercali
+│       fragilist
+│       ic
+└── two
+    lines of text
+"#
+    );
+}
+
+#[test]
+fn wrap_wide_characters() {
+    let options = RenderOptions { wrap: Some(LineWidth::Columns(9)), ..Default::default() };
+    let tree = Tree::new("x").with_leaves(["日本語テキスト"]).with_options(options);
+    assert_eq!(tree.to_string(), "x\n└── 日本\n    語テ\n    キス\n    ト\n");
+}
+
+#[test]
+fn truncate_lines_to_width() {
+    let options = RenderOptions {
+        truncate: Some(LineWidth::Columns(12)),
+        max_children: Some(2),
+        ..Default::default()
+    };
+    let tree = Tree::new("a root wider than twelve")
+        .with_leaves([
+            Tree::new("abcdefgh").with_leaves(["abcd", "abcde", "日本語"]),
+            Tree::new("fits"),
+            Tree::new("hidden"),
+        ])
+        .with_options(options);
+    assert_eq!(
+        tree.to_string(),
+        r#"a root wide…
+├── abcdefgh
+│   ├── abcd
+│   ├── abc…
+│   └── … 1…
+├── fits
+└── … 1 more
+"#
+    );
+
+    let tree = Tree::new("x").with_leaves(["日本語"]).with_options(RenderOptions {
+        truncate: Some(LineWidth::Columns(8)),
+        ..Default::default()
+    });
+    assert_eq!(tree.to_string(), "x\n└── 日…\n");
+
+    // Deep enough, the glyphs are cut too.
+    use unicode_width::UnicodeWidthStr;
+    let mut tree = Tree::new("d".to_owned());
+    for depth in (0..4).rev() {
+        tree = Tree::new(depth.to_string()).with_leaves([tree, Tree::new("x".to_owned())]);
+    }
+    let tree = tree.with_options(RenderOptions {
+        truncate: Some(LineWidth::Columns(8)),
+        ..Default::default()
+    });
+    let rendered = tree.to_string();
+    assert!(rendered.lines().all(|line| line.width() <= 8), "{}", rendered);
+    assert_eq!(
+        rendered,
+        r#"0
+├── 1
+│   ├──…
+│   │  …
+│   │  …
+│   │  …
+│   │  …
+│   └──…
+└── x
+"#
+    );
+}
+
+#[test]
+fn annotations_are_right_aligned() {
+    let tree = Tree::new("root")
+        .with_leaves([
+            Tree::new("a").with_annotation("1").with_leaves([
+                Tree::new("deep label").with_annotation("1.5K"),
+                Tree::new("two\nlines").with_annotation("22"),
+            ]),
+            Tree::new("unannotated"),
+            Tree::new("日本").with_annotation("333"),
+        ])
+        .with_options(RenderOptions { max_children: Some(2), ..Default::default() });
+    assert_eq!(
+        tree.to_string(),
+        r#"root
+├── a                  1
+│   ├── deep label  1.5K
+│   └── two           22
+│       lines
+├── unannotated
+└── … 1 more
+"#
+    );
+}
+
+#[test]
+fn annotations_fit_in_wrapped_and_truncated_lines() {
+    use unicode_width::UnicodeWidthStr;
+    let tree = || {
+        Tree::new("root").with_leaves([
+            Tree::new("a big label").with_annotation("2.9K"),
+            Tree::new("small").with_annotation("12"),
+        ])
+    };
+
+    let truncated = tree()
+        .with_options(RenderOptions {
+            truncate: Some(LineWidth::Columns(14)),
+            ..Default::default()
+        })
+        .to_string();
+    assert!(truncated.lines().all(|line| line.width() <= 14), "{}", truncated);
+    assert_eq!(truncated, "root\n├── a b…  2.9K\n└── sma…    12\n");
+
+    let wrapped = tree()
+        .with_options(RenderOptions { wrap: Some(LineWidth::Columns(16)), ..Default::default() })
+        .to_string();
+    assert!(wrapped.lines().all(|line| line.width() <= 16), "{}", wrapped);
+    assert_eq!(wrapped, "root\n├── a big  2.9K\n│   label\n└── small    12\n");
+}
+
+#[test]
+fn parse_round_trips_every_preset() {
+    let tree = || {
+        Tree::new("root".to_owned()).with_leaves([
+            Tree::new("a".to_owned()).with_leaves([
+                Tree::new("two\nlines".to_owned()).with_leaves(["x".to_owned()]),
+                Tree::new("b".to_owned()),
+            ]),
+            Tree::new("c\n\nd".to_owned()).with_leaves(["e".to_owned()]),
+        ])
+    };
+    let presets = ["box", "ascii", "rounded", "heavy", "double"];
+    for glyphs in presets.map(|name| GlyphPalette::from(name.parse::<GlyphPreset>().unwrap())) {
+        let text = tree().with_palette(glyphs.clone()).to_string();
+        let parsed = Tree::parse(&text, &glyphs).unwrap();
+        assert_eq!(parsed.leaves[1].root, "c\n\nd");
+        assert_eq!(parsed.with_palette(glyphs.clone()).to_string(), text);
+
+        // Editors strip trailing whitespace.
+        let stripped: String = text.lines().map(|line| format!("{}\n", line.trim_end())).collect();
+        let parsed: Tree<String> = stripped.parse().unwrap();
+        assert_eq!(parsed.with_palette(glyphs).to_string(), text);
+    }
+}
+
+#[test]
+fn parse_indent_only_and_errors() {
+    let text = "root\n    a\n        b\n    c\n";
+    let tree = Tree::parse(text, &GlyphPalette::indent_only()).unwrap();
+    assert_eq!(tree.leaves.len(), 2);
+    assert_eq!(tree.leaves[0].leaves[0].root, "b");
+
+    let tree: Tree<String> = "just a\nroot\n\n".parse().unwrap();
+    assert_eq!(tree.root, "just a\nroot");
+    assert!(tree.leaves.is_empty());
+
+    assert!(matches!(Tree::parse(" \n", &GlyphPalette::new()), Err(ParseError::Empty)));
+    let error =
+        Tree::parse("foo\n├── bar\n│   └── baz\nqux\n", &GlyphPalette::new()).err().unwrap();
+    assert!(matches!(error, ParseError::Indentation { line: 4, column: 1 }));
+    let error =
+        Tree::parse("foo\n└── bar\n    ├── baz\n    ?? qux", &GlyphPalette::new()).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 4, column 5: expected a branch glyph or the continuation of a label"
+    );
+}
+
+#[test]
+fn outline_with_tabs_and_blank_lines() {
+    let tree = Tree::from_outline("root\n\ta\n\t\tb\n\n\tc\n").unwrap();
+    assert_eq!(tree.to_string(), "root\n├── a\n│   └── b\n└── c\n");
+
+    let tree = Tree::from_outline("  root\n    a\n      b\n    c\n      d\n").unwrap();
+    assert_eq!(tree.leaves[1].leaves[0].root, "d");
+}
+
+#[test]
+fn outline_errors_carry_positions() {
+    assert!(matches!(Tree::from_outline("\n  \n"), Err(ParseError::Empty)));
+
+    let error = Tree::from_outline("root\n    a\n      b\n  c\n").err().unwrap();
+    assert!(matches!(error, ParseError::Dedent { line: 4, column: 3 }), "{}", error);
+
+    let error = Tree::from_outline("root\n\ta\n    b\n").err().unwrap();
+    assert_eq!(error.to_string(), "line 3, column 1: indentation doesn't match any outer level");
+
+    let error = Tree::from_outline("root\n  a\nother root\n").err().unwrap();
+    assert!(matches!(error, ParseError::SecondRoot { line: 3, column: 1 }), "{}", error);
+}
+
+#[test]
+fn markdown_nested_lists() {
+    let markdown = "Intro paragraph.
+
+* root
+  - a
+    continued
+    1) b
+    2) [x] c
+  + d
+
+Trailing paragraph.
+
+- not part of the tree
+";
+    let tree = Tree::from_markdown(markdown).unwrap();
+    assert_eq!(
+        tree.to_string(),
+        "root
+├── a
+│   continued
+│   ├── b
+│   └── [x] c
+└── d
+"
+    );
+
+    let error = Tree::from_markdown("- a\n- b\n").err().unwrap();
+    assert!(matches!(error, ParseError::SecondRoot { line: 2, column: 1 }), "{}", error);
+    let error = Tree::from_markdown("- a\n    - b\n  - c\n").err().unwrap();
+    assert!(matches!(error, ParseError::Dedent { line: 3, column: 3 }), "{}", error);
+    assert!(matches!(Tree::from_markdown("# Just a title\n"), Ok(tree) if tree.leaves.is_empty()));
+    assert!(matches!(Tree::from_markdown("no list\n"), Err(ParseError::Empty)));
+}
+
+#[test]
+fn mermaid_flowchart_escapes_labels() {
+    let tree = Tree::new("root")
+        .with_leaves([Tree::new("say \"hi\"").with_leaves(["<b>#1</b>"]), Tree::new("two\nlines")]);
+    let options =
+        MermaidOptions { keyword: MermaidKeyword::Flowchart, direction: Direction::BottomUp };
+    assert_eq!(
+        tree.to_mermaid(&options),
+        r##"flowchart BT
+    n["root"]
+    n --> n_0["say #quot;hi#quot;"]
+    n_0 --> n_0_0["#lt;b#gt;#35;1#lt;/b#gt;"]
+    n --> n_1["two<br>lines"]
+"##
+    );
+    assert_eq!("TB".parse::<Direction>().unwrap(), Direction::TopDown);
+    assert_eq!(Direction::RightLeft.to_string(), "RL");
+}
+
+#[test]
+fn mermaid_flowchart_import() {
+    let flowchart = r#"%% comment
+graph LR
+    root(("root")) --> a[a] & b{"b; #quot;quoted#quot;"}
+    a -->|label| c([c<br>two lines]) --- d>d]
+    b -.-> e:::highlight; b -- text --> f
+    style root fill:#f9f
+"#;
+    let tree = Tree::from_mermaid(flowchart).unwrap();
+    assert_eq!(
+        tree.to_string(),
+        r#"root
+├── a
+│   └── c
+│       two lines
+│       └── d
+└── b; "quoted"
+    ├── e
+    └── f
+"#
+    );
+
+    // What we export reads back the same.
+    let exported = tree.to_mermaid(&MermaidOptions::default());
+    assert_eq!(Tree::from_mermaid(&exported).unwrap().to_string(), tree.to_string());
+}
+
+#[test]
+fn mermaid_flowchart_accessibility_and_trapezoids() {
+    // Only the `accTitle` and `accDescr` keywords are skipped, not IDs that start with `acc`.
+    let flowchart = "flowchart TD\n accTitle: Tables\n accDescr {\n which --> ones\n }\n\
+                     accounts --> users\n accounts --> roles\n accDescr: done\n";
+    let tree = Tree::from_mermaid(flowchart).unwrap();
+    assert_eq!(tree.to_string(), "accounts\n├── users\n└── roles\n");
+
+    let tree = Tree::from_mermaid("graph TD\nA[/in\\] --> B[\\out/]\nB --> C[/\"a\"/]\n").unwrap();
+    assert_eq!(tree.to_string(), "in\n└── out\n    └── a\n");
+}
+
+#[test]
+fn mermaid_errors_point_at_lines() {
+    let markdown = "# Title\n\n```mermaid\ngraph TD\n  A --> B\n  C --> B\n```\n";
+    let error = Tree::from_mermaid(markdown).err().unwrap();
+    assert_eq!(error.to_string(), "line 6: `B` already has a parent, the flowchart isn't a tree");
+
+    let error = Tree::from_mermaid("graph TD\nA --> B\nB --> A\n").err().unwrap();
+    assert!(matches!(error, MermaidError::Cycle { line: 3, .. }), "{}", error);
+
+    let error = Tree::from_mermaid("graph TD\nA --> B\nC[unclosed\n").err().unwrap();
+    assert_eq!(error.to_string(), "line 3: can't read `C[unclosed`");
+
+    let error = Tree::from_mermaid("A --> B\nC\n").err().unwrap();
+    assert!(matches!(error, MermaidError::SecondRoot { line: 2, .. }), "{}", error);
+    assert!(matches!(Tree::from_mermaid("graph TD\n"), Err(MermaidError::Empty)));
+}
+
+#[test]
+fn mermaid_mindmap_import() {
+    let markdown = r#"```mermaid
+graph TD
+  A --> B
+```
+
+```mermaid
+mindmap
+  root((mindmap))
+    Origins
+      Long history
+      ::icon(fa fa-book)
+      Popularisation
+        British popular psychology author Tony Buzan
+    Research
+      id1)On effectiveness<br>and features(
+      id2))"On #quot;automatic#quot; creation"((
+        :::urgent large
+    Tools
+        Pen and paper
+      id3{{Mermaid}}
+      f (x)
+```
+"#;
+    let tree = Tree::from_mindmap(markdown).unwrap();
+    assert_eq!(
+        tree.to_string(),
+        r#"mindmap
+├── Origins
+│   ├── Long history
+│   └── Popularisation
+│       └── British popular psychology author Tony Buzan
+├── Research
+│   ├── On effectiveness
+│   │   and features
+│   └── On "automatic" creation
+└── Tools
+    ├── Pen and paper
+    ├── Mermaid
+    └── f (x)
+"#
+    );
+    assert_eq!(tree.root.shape, MindmapShape::Circle);
+    assert_eq!(tree.root.id.as_deref(), Some("root"));
+    assert_eq!(tree.leaves[0].leaves[0].root.icon.as_deref(), Some("fa fa-book"));
+    let research = &tree.leaves[1].leaves;
+    assert_eq!(research[0].root.shape, MindmapShape::Cloud);
+    assert_eq!(research[1].root.shape, MindmapShape::Bang);
+    assert_eq!(research[1].root.class.as_deref(), Some("urgent large"));
+    assert_eq!(tree.leaves[2].leaves[1].root.shape, MindmapShape::Hexagon);
+
+    // What we export reads back the same, shapes and decorations included.
+    let exported = tree.to_mindmap();
+    assert_eq!(Tree::from_mindmap(&exported).unwrap().to_mindmap(), exported);
+}
+
+#[test]
+fn mermaid_mindmap_export() {
+    let tree = Tree::new(MindmapNode::new("root").with_shape(MindmapShape::Hexagon)).with_leaves([
+        Tree::new(MindmapNode::new("f(x)")),
+        Tree::new(MindmapNode::new("(a) \"b\"").with_shape(MindmapShape::Rounded)),
+        Tree::new(MindmapNode::new("two\nlines")),
+    ]);
+    assert_eq!(
+        tree.to_mindmap(),
+        r##"mindmap
+  n{{root}}
+    n_0["f(x)"]
+    n_1("(a) #quot;b#quot;")
+    two<br>lines
+"##
+    );
+    assert_eq!(
+        Tree::new("plain").with_leaves(["text"]).to_mindmap(),
+        "mindmap\n  plain\n    text\n"
+    );
+}
+
+#[test]
+fn mermaid_mindmap_errors_point_at_lines() {
+    let error = Tree::from_mindmap("mindmap\n  a\n    b\n  c\n").err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 4: `c` is a second node without a parent, `a` is the first"
+    );
+
+    let error = Tree::from_mindmap("mindmap\n  ::icon(fa fa-book)\n  a\n").err().unwrap();
+    assert_eq!(error.to_string(), "line 2: can't read `::icon(fa fa-book)`");
+
+    let error = Tree::from_mindmap("mindmap\n  a\n    ::iccon(x)\n").err().unwrap();
+    assert!(matches!(error, MermaidError::Syntax { line: 3, .. }), "{}", error);
+    assert!(matches!(Tree::from_mindmap("mindmap\n"), Err(MermaidError::Empty)));
+    assert!(matches!(Tree::from_mindmap("```mermaid\ngraph TD\n```\n"), Err(MermaidError::Empty)));
+}
+
+#[test]
+fn dot_graph_styles() {
+    let tree = Tree::new("root").with_style("red.on_235".parse().unwrap()).with_leaves([
+        Tree::new("say \"hi\"\\").with_style("bold.underlined.22".parse().unwrap()),
+        Tree::new("<a & b>\nc").with_style("underlined".parse().unwrap()),
+    ]);
+    let options = DotOptions { direction: Direction::BottomUp, styles: true, ..Default::default() };
+    assert_eq!(
+        tree.to_dot(&options),
+        r##"digraph tree {
+    rankdir=BT;
+    n [label="root", color="red", fontcolor="red", style=filled, fillcolor="#262626"];
+    n_0 [label=<<B><U>say &quot;hi&quot;\</U></B>>, color="#005f00", fontcolor="#005f00"];
+    n -> n_0;
+    n_1 [label=<<U>&lt;a &amp; b&gt;<BR/>c</U>>];
+    n -> n_1;
+}
+"##
+    );
+
+    // Without styles, only the labels are kept.
+    let options = DotOptions::default();
+    assert!(tree.to_dot(&options).contains("    n_0 [label=\"say \\\"hi\\\"\\\\\"];\n"));
+    assert!(tree.to_dot(&options).contains("    n_1 [label=\"<a & b>\\nc\"];\n"));
+}
+
+#[test]
+fn dot_binary_tree_keeps_sides() {
+    let tree = BinaryTree::new(1)
+        .with_left(BinaryTree::new(2).with_left(BinaryTree::new(4)))
+        .with_right(BinaryTree::new(3));
+    assert_eq!(
+        tree.to_dot(&DotOptions::default()),
+        r#"digraph tree {
+    rankdir=TB;
+    ordering=out;
+    n [label="1"];
+    nL [label="2"];
+    nLL [label="4"];
+    nL -> nLL;
+    nLR [label="", style=invis];
+    nL -> nLR [style=invis];
+    n -> nL;
+    nR [label="3"];
+    n -> nR;
+}
+"#
+    );
+}
+
+#[test]
+fn serde_tree_round_trip() {
+    let options = RenderOptions {
+        glyphs: GlyphPalette::ascii(),
+        color: ColorChoice::Always,
+        depth_styles: vec!["red".parse().unwrap()],
+        wrap: Some(LineWidth::Terminal),
+        truncate: Some(LineWidth::Columns(40)),
+        ..Default::default()
+    };
+    let tree = Tree::new("src".to_owned())
+        .with_style("bold.blue".parse().unwrap())
+        .with_annotation("12K")
+        .with_leaves([
+            Tree::new("lib.rs".to_owned()).with_glyphs(GlyphPalette::heavy()),
+            Tree::new("two\nlines".to_owned()).with_multiline(Multiline::False),
+        ])
+        .with_options(options);
+
+    let json = serde_json::to_value(&tree).unwrap();
+    assert_eq!(json["style"], "bold.blue");
+    assert_eq!(json["leaves"][1]["multiline"], "false");
+    assert_eq!(json["leaves"][1].get("leaves"), None);
+    assert_eq!(json["options"]["color"], "always");
+    assert_eq!(json["options"]["wrap"], "auto");
+    assert_eq!(json["options"]["truncate"], 40);
+    assert_eq!(json["options"].get("max_depth"), None);
+
+    // Default glyphs and color are left out like the other defaults.
+    let plain =
+        Tree::new("src").with_options(RenderOptions { max_depth: Some(1), ..Default::default() });
+    assert_eq!(
+        serde_json::to_string(&plain).unwrap(),
+        r#"{"root":"src","options":{"max_depth":1}}"#
+    );
+
+    let read: Tree<String> = serde_json::from_value(json).unwrap();
+    assert_eq!(read.to_string(), tree.to_string());
+    assert_eq!(read.options(), tree.options());
+    assert_eq!(serde_json::to_string(&read).unwrap(), serde_json::to_string(&tree).unwrap());
+
+    let minimal: Tree<u32> =
+        serde_json::from_str(r#"{"root": 1, "leaves": [{"root": 2}]}"#).unwrap();
+    assert_eq!(minimal.to_string(), "1\n└── 2\n");
+    assert!(serde_json::from_str::<Tree<u32>>(r#"{"root": 1, "style": "blurple"}"#).is_err());
+}
+
+#[test]
+fn yaml_document_tree() {
+    let yaml = r#"
+# Comments are skipped
+name: treeleaf
+version: 0.1
+empty: []
+nothing: ~
+description: |
+  Two
+  lines
+matrix:
+  - os: linux
+    targets: [x86_64, aarch64]
+  - os: macos
+? [complex, key]
+: true
+"#;
+    assert_eq!(
+        Tree::from_yaml(yaml).unwrap().to_string(),
+        r#".
+├── name: treeleaf
+├── version: 0.1
+├── empty: []
+├── nothing: null
+├── description: Two
+│   lines
+├── matrix
+│   ├── [0]
+│   │   ├── os: linux
+│   │   └── targets
+│   │       ├── [0]: x86_64
+│   │       └── [1]: aarch64
+│   └── [1]
+│       └── os: macos
+└── [complex, key]: true
+"#
+    );
+
+    let stream = Tree::from_yaml("--- a\n--- [b]\n").unwrap();
+    assert_eq!(stream.to_string(), ".\n├── [0]: a\n└── [1]\n    └── [0]: b\n");
+    assert_eq!(Tree::from_yaml("just a scalar").unwrap().to_string(), "just a scalar\n");
+    assert!(Tree::from_yaml("a: [b").is_err());
+}
+
+#[test]
+fn toml_document_tree() {
+    let toml = r#"
+title = "example"
+ratio = 1.0
+released = 1979-05-27T07:32:00Z
+
+[[bin]]
+name = "treeleaf"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+"#;
+    assert_eq!(
+        Tree::from_toml(toml).unwrap().to_string(),
+        r#".
+├── title: example
+├── ratio: 1.0
+├── released: 1979-05-27T07:32:00Z
+├── bin
+│   └── [0]
+│       └── name: treeleaf
+└── dependencies
+    └── serde
+        ├── version: 1
+        └── features
+            └── [0]: derive
+"#
+    );
+    let error = Tree::from_toml("a = ").err().unwrap();
+    assert!(error.to_string().starts_with("invalid TOML: "), "{}", error);
+}
+
+#[test]
+fn json_document_tree() {
+    let json: serde_json::Value = serde_json::from_str(
+        r#"{"z": {"nested": [[], {}, [1.5, true]]}, "a": "line\nbreak", "unicode": "héllo wörld"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Tree::from_json(&json, &JsonOptions::default()).to_string(),
+        r#".
+├── z
+│   └── nested
+│       ├── [0]: []
+│       ├── [1]: {}
+│       └── [2]
+│           ├── [0]: 1.5
+│           └── [1]: true
+├── a: "line\nbreak"
+└── unicode: "héllo wörld"
+"#
+    );
+
+    let options = JsonOptions { max_string: Some(5), max_items: Some(0), ..Default::default() };
+    assert_eq!(
+        Tree::from_json(&json, &options).to_string(),
+        r#".
+├── z
+│   └── nested
+│       └── … 3 more items
+├── a: "line\n…"
+└── unicode: "héllo…"
+"#
+    );
+    assert_eq!(Tree::from_json(&serde_json::json!(42), &options).to_string(), ".: 42\n");
+    let options = JsonOptions { types: true, ..Default::default() };
+    assert_eq!(
+        Tree::from_json(&serde_json::json!("x"), &options).to_string(),
+        ". (string): \"x\"\n"
+    );
+}
+
+#[test]
+fn html_page() {
+    let tree = Tree::new("<root>")
+        .with_leaves([
+            Tree::new("a").with_style("bold.red".parse().unwrap()).with_leaves(["two\nlines"]),
+            Tree::new("b").with_annotation("1K"),
+        ])
+        .with_palette(GlyphPalette::ascii());
+    let options = HtmlOptions { css: true, expanded_depth: Some(1), ..Default::default() };
+    let html = tree.to_html(&options);
+    assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>&lt;root&gt;</title>\n<style>\n"));
+    assert!(!html.contains("<script>") && !html.contains("type=\"search\""));
+    let body = &html[html.find("<ul class=\"treeleaf\">").unwrap()..];
+    assert_eq!(
+        body,
+        r#"<ul class="treeleaf">
+<li><details open><summary class="node"><span class="label">&lt;root&gt;</span></summary>
+<ul>
+<li><details><summary class="node"><span class="glyphs">|-- </span><span class="label" style="color: red; font-weight: bold">a</span></summary>
+<ul>
+<li><span class="node"><span class="glyphs">|   `-- </span><span class="label">two<br><span class="glyphs">|       </span>lines</span></span></li>
+</ul>
+</details></li>
+<li><span class="node"><span class="glyphs">`-- </span><span class="label">b</span>  <span class="annotation">1K</span></span></li>
+</ul>
+</details></li>
+</ul>
+</body>
+</html>
+"#
+    );
+
+    // Plain nested lists without CSS, and a search box that needs JavaScript to show up.
+    let options = HtmlOptions { title: Some("T".to_owned()), search: true, ..Default::default() };
+    let html = tree.to_html(&options);
+    assert!(html.contains("<title>T</title>\n</head>"));
+    assert!(html.contains("<input type=\"search\" class=\"treeleaf-search\" placeholder=\"Search\" aria-label=\"Search\" hidden>"));
+    assert!(html.contains("<li><details open><summary class=\"node\"><span class=\"label\" style=\"color: red; font-weight: bold\">a</span></summary>"));
+    assert!(html.contains("<span class=\"label\">two<br>lines</span>"));
+    assert!(html.contains("<script>\n(() => {"));
+}
+
+#[test]
+fn svg_tidy_layout() {
+    let tree = Tree::new("foo").with_leaves(["bar", "b<z"]);
+    assert_eq!(
+        tree.to_svg(&SvgOptions::default()),
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="126.4" height="113" viewBox="0 0 126.4 113" font-family="ui-monospace, monospace" font-size="14">
+<g fill="none" stroke="#888">
+<path d="M63.2 40.5V56.5H35.6V72.5"/>
+<path d="M63.2 40.5V56.5H90.8V72.5"/>
+</g>
+<rect x="43.6" y="16" width="39.2" height="24.5" rx="4" fill="white" stroke="#444"/>
+<text text-anchor="middle"><tspan x="63.2" y="33.5" xml:space="preserve">foo</tspan></text>
+<rect x="16" y="72.5" width="39.2" height="24.5" rx="4" fill="white" stroke="#444"/>
+<text text-anchor="middle"><tspan x="35.6" y="90" xml:space="preserve">bar</tspan></text>
+<rect x="71.2" y="72.5" width="39.2" height="24.5" rx="4" fill="white" stroke="#444"/>
+<text text-anchor="middle"><tspan x="90.8" y="90" xml:space="preserve">b&lt;z</tspan></text>
+</svg>
+"##
+    );
+}
+
+#[test]
+fn svg_subtrees_dont_overlap() {
+    let rects = |svg: &str| -> Vec<(f64, f64, f64)> {
+        let number = |rect: &str, name: &str| -> f64 {
+            let start = rect.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+            rect[start..].split('"').next().unwrap().parse().unwrap()
+        };
+        svg.lines()
+            .filter(|line| line.starts_with("<rect"))
+            .map(|rect| (number(rect, "y"), number(rect, "x"), number(rect, "width")))
+            .collect()
+    };
+
+    // A wide subtree on the left and a deep one on the right share no column at any level.
+    let tree = Tree::new("root").with_leaves([
+        Tree::new("a").with_leaves(["a rather wide leaf", "and another wide one"]),
+        Tree::new("b"),
+        Tree::new("c").with_leaves([Tree::new("c1").with_leaves(["two\nlines", "x"])]),
+    ]);
+    let mut boxes = rects(&tree.to_svg(&SvgOptions::default()));
+    assert_eq!(boxes.len(), 9);
+    boxes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for pair in boxes.windows(2) {
+        let ((y1, x1, width1), (y2, x2, _)) = (pair[0], pair[1]);
+        if y1 == y2 {
+            assert!(x1 + width1 + 16.0 <= x2 + 0.1, "{:?} overlaps {:?}", pair[0], pair[1]);
+        }
+    }
+
+    // An only child leans towards its side, wide labels are measured in columns.
+    let svg = BinaryTree::new("1").with_right(BinaryTree::new("界")).to_svg(&SvgOptions::default());
+    let boxes = rects(&svg);
+    assert!(boxes[1].1 > boxes[0].1 + boxes[0].2 / 2.0, "{:?}", boxes);
+    assert_eq!(boxes[1].2, 2.0 * 8.4 + 14.0);
+}
+
+#[test]
+fn svg_elision() {
+    let labels = |svg: &str| -> Vec<String> {
+        svg.split("xml:space=\"preserve\">")
+            .skip(1)
+            .map(|text| text.split('<').next().unwrap().to_owned())
+            .collect()
+    };
+    let tree = Tree::new("root").with_leaves([
+        Tree::new("a").with_leaves([Tree::new("a1").with_leaves(["a11"]), Tree::new("a2")]),
+        Tree::new("b"),
+        Tree::new("c"),
+    ]);
+    let options = SvgOptions { max_depth: Some(1), max_children: Some(2), ..Default::default() };
+    assert_eq!(labels(&tree.to_svg(&options)), ["root", "a", "[+ 3 descendants]", "b", "… 1 more"]);
+
+    // Binary trees are cut the same way.
+    let tree = BinaryTree::new(1).with_left(BinaryTree::new(2)).with_right(BinaryTree::new(3));
+    let options = SvgOptions { max_children: Some(1), ..Default::default() };
+    assert_eq!(labels(&tree.to_svg(&options)), ["1", "2", "… 1 more"]);
+    let options = SvgOptions { max_depth: Some(0), ..Default::default() };
+    assert_eq!(labels(&tree.to_svg(&options)), ["1", "[+ 2 descendants]"]);
+}
+
+#[test]
+fn markup_lists() {
+    let tree = Tree::new("docs")
+        .with_leaves([
+            Tree::new("*draft*.md").with_annotation("1.2K"),
+            Tree::new("1. intro\n- notes"),
+            Tree::new("a]b->c"),
+        ])
+        .with_options(RenderOptions { color: ColorChoice::Always, ..Default::default() });
+    assert_eq!(
+        tree.to_markdown(MarkupStyle::List),
+        "- docs\n  - \\*draft\\*.md (1.2K)\n  - 1\\. intro\\\n    \\- notes\n  - a\\]b-\\>c\n"
+    );
+    assert_eq!(
+        tree.to_asciidoc(MarkupStyle::List),
+        "* docs\n** pass:c[*draft*.md (1.2K)]\n** pass:c[1. intro] +\npass:c[- notes]\n\
+         ** pass:c[a\\]b->c]\n"
+    );
+}
+
+#[test]
+fn markup_code_blocks() {
+    let tree = Tree::new("```\n-----")
+        .with_style("red".parse().unwrap())
+        .with_leaves([Tree::new("leaf").with_style("blue".parse().unwrap())])
+        .with_options(RenderOptions { color: ColorChoice::Always, ..Default::default() });
+    // No colors, and fences longer than the lines that would close them.
+    assert_eq!(tree.to_markdown(MarkupStyle::Code), "````text\n```\n-----\n└── leaf\n````\n");
+    assert_eq!(tree.to_asciidoc(MarkupStyle::Code), "------\n```\n-----\n└── leaf\n------\n");
+}
+
+#[test]
+fn csv_rows() {
+    let tree = Tree::new("root")
+        .with_leaves([Tree::new("say \"hi\"").with_leaves(["two\nlines"]), Tree::new("plain")]);
+    assert_eq!(
+        tree.to_csv(),
+        "id,parent_id,depth,path,label\n0,,0,root,root\n\
+         1,0,1,\"root/say \"\"hi\"\"\",\"say \"\"hi\"\"\"\n\
+         2,1,2,\"root/say \"\"hi\"\"/two\nlines\",\"two\nlines\"\n3,0,1,root/plain,plain\n"
+    );
+}
+
+#[test]
+fn traversal_orders_and_positions() {
+    //  a
+    //  ├── b
+    //  │   ├── d
+    //  │   └── e
+    //  └── c
+    //      └── f
+    let tree = Tree::new('a')
+        .with_leaves([Tree::new('b').with_leaves(['d', 'e']), Tree::new('c').with_leaves(['f'])]);
+    let walk = |visits: Vec<Visit<&Tree<char>>>| -> Vec<(char, usize, usize, bool, Vec<usize>)> {
+        visits.into_iter().map(|v| (v.node.root, v.depth, v.index, v.is_last, v.path)).collect()
+    };
+    let a = ('a', 0, 0, true, vec![]);
+    let b = ('b', 1, 0, false, vec![0]);
+    let c = ('c', 1, 1, true, vec![1]);
+    let d = ('d', 2, 0, false, vec![0, 0]);
+    let e = ('e', 2, 1, true, vec![0, 1]);
+    let f = ('f', 2, 0, true, vec![1, 0]);
+    assert_eq!(walk(tree.pre_order().collect()), [&a, &b, &d, &e, &c, &f].map(Clone::clone));
+    assert_eq!(walk(tree.post_order().collect()), [&d, &e, &b, &f, &c, &a].map(Clone::clone));
+    assert_eq!(walk(tree.breadth_first().collect()), [a, b, c, d, e, f]);
+
+    // Mutable walks meet the nodes in the same order, with the same positions.
+    let mut tree = tree;
+    let mut order = String::new();
+    for visit in tree.post_order_mut() {
+        order.push(*visit.node.root);
+        *visit.node.root = visit.node.root.to_ascii_uppercase();
+        *visit.node.annotation = Some(format!("{:?}", visit.path));
+    }
+    assert_eq!(order, "debfca");
+    let order: String = tree.pre_order_mut().map(|visit| *visit.node.root).collect();
+    assert_eq!(order, "ABDECF");
+    for visit in tree.breadth_first_mut() {
+        if visit.is_last && visit.depth > 0 {
+            *visit.node.style = Some("bold".parse().unwrap());
+        }
+    }
+    assert_eq!(tree.get(&[0, 1]).unwrap().annotation.as_deref(), Some("[0, 1]"));
+    let styled: String = tree
+        .pre_order()
+        .filter(|visit| visit.node.style.is_some())
+        .map(|visit| visit.node.root)
+        .collect();
+    assert_eq!(styled, "ECF");
+    assert!(tree.get(&[2]).is_none());
+}
+
+#[test]
+fn transforms_keep_node_settings_and_options() {
+    let options = RenderOptions {
+        glyphs: GlyphPalette::ascii(),
+        max_children: Some(5),
+        ..Default::default()
+    };
+    let tree = Tree::new(1)
+        .with_annotation("root")
+        .with_leaves([
+            Tree::new(2).with_leaves([Tree::new(3).with_style("red".parse().unwrap())]),
+            Tree::new(4).with_multiline(Multiline::False),
+        ])
+        .with_options(options.clone());
+
+    let mut seen = Vec::new();
+    let doubled = tree.map(|n| {
+        seen.push(n);
+        n * 2
+    });
+    assert_eq!(seen, [1, 2, 3, 4]);
+    assert_eq!(doubled.options(), &options);
+    assert_eq!(doubled.annotation.as_deref(), Some("root"));
+    assert_eq!(doubled.get(&[0, 0]).unwrap().style, Some("red".parse().unwrap()));
+    assert_eq!(doubled.get(&[1]).unwrap().multiline, Multiline::False);
+    assert_eq!(
+        doubled.fold(|n, leaves: Vec<String>| format!("{}({})", n, leaves.concat())),
+        "2(4(6())8())"
+    );
+
+    // The first error in pre-order wins, and `f` isn't called after it.
+    let mut calls = 0;
+    let result = doubled.try_map(|n| {
+        calls += 1;
+        if n > 2 {
+            Err(n)
+        } else {
+            Ok(n.to_string())
+        }
+    });
+    assert_eq!(result.err(), Some(4));
+    assert_eq!(calls, 2);
+
+    let mut tree = Tree::new("a")
+        .with_leaves([
+            Tree::new("b").with_leaves([Tree::new("c").with_leaves(["match"]), Tree::new("d")]),
+            Tree::new("match").with_leaves(["e"]),
+            Tree::new("f"),
+        ])
+        .with_options(options.clone());
+    tree.retain(|label| *label == "match");
+    assert_eq!(tree.to_string(), "a\n|-- b\n|   `-- c\n|       `-- match\n`-- match\n");
+    assert_eq!(tree.options(), &options);
+
+    tree.prune(|label| *label == "c");
+    assert_eq!(tree.to_string(), "a\n|-- b\n`-- match\n");
+    tree.retain(|_| false);
+    assert_eq!(tree.to_string(), "a\n");
+}
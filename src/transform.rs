@@ -0,0 +1,167 @@
+//! Whole-tree transformations: relabeling, folding and filtering, keeping the styles,
+//! annotations and rendering options of the nodes that stay.
+
+use std::{convert::Infallible, fmt::Display};
+
+use crate::Tree;
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// Turns every label into another, root first and then down each leaf in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::{GlyphPalette, Tree};
+    ///
+    /// let sizes = Tree::new(4096).with_leaves([1024, 3072]).with_palette(GlyphPalette::ascii());
+    /// let labels = sizes.map(|bytes| format!("{}K", bytes / 1024));
+    /// assert_eq!(labels.to_string(), "4K\n|-- 1K\n`-- 3K\n");
+    /// ```
+    pub fn map<E, F>(self, mut f: F) -> Tree<E>
+    where
+        E: Display,
+        F: FnMut(D) -> E,
+    {
+        match self.try_map(|root| Ok::<_, Infallible>(f(root))) {
+            Ok(tree) => tree,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Turns every label into another like [`Tree::map`], stopping at the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new("1").with_leaves(["2", "3"]);
+    /// let numbers = tree.try_map(|label| label.parse::<u8>()).unwrap();
+    /// assert_eq!(numbers.fold(|n, sums: Vec<u8>| n + sums.iter().sum::<u8>()), 6);
+    ///
+    /// assert!(Tree::new("1").with_leaves(["two"]).try_map(|label| label.parse::<u8>()).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error returned by `f`.
+    pub fn try_map<E, Err, F>(self, mut f: F) -> Result<Tree<E>, Err>
+    where
+        E: Display,
+        F: FnMut(D) -> Result<E, Err>,
+    {
+        self.try_map_with(&mut f)
+    }
+
+    fn try_map_with<E, Err, F>(self, f: &mut F) -> Result<Tree<E>, Err>
+    where
+        E: Display,
+        F: FnMut(D) -> Result<E, Err>,
+    {
+        let Tree { root, leaves, multiline, glyphs, style, annotation, options } = self;
+        let root = f(root)?;
+        let leaves =
+            leaves.into_iter().map(|leaf| leaf.try_map_with(f)).collect::<Result<_, _>>()?;
+        Ok(Tree { root, leaves, multiline, glyphs, style, annotation, options })
+    }
+
+    /// Sums the tree up from the leaves: `f` gets each label with what it returned for the
+    /// node's leaves, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new("a").with_leaves([Tree::new("b").with_leaves(["c"]), Tree::new("d")]);
+    /// let height = tree.fold(|_, heights: Vec<usize>| 1 + heights.into_iter().max().unwrap_or(0));
+    /// assert_eq!(height, 3);
+    /// ```
+    pub fn fold<B, F>(&self, mut f: F) -> B
+    where
+        F: FnMut(&D, Vec<B>) -> B,
+    {
+        self.fold_with(&mut f)
+    }
+
+    fn fold_with<B, F>(&self, f: &mut F) -> B
+    where
+        F: FnMut(&D, Vec<B>) -> B,
+    {
+        let leaves = self.leaves.iter().map(|leaf| leaf.fold_with(f)).collect();
+        f(&self.root, leaves)
+    }
+
+    /// Keeps the nodes whose label matches, along with their ancestors so that the tree stays
+    /// connected. The root always stays, even if nothing matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let mut tree = Tree::new("src").with_leaves([
+    ///     Tree::new("bin").with_leaves(["main.rs", "build.sh"]),
+    ///     Tree::new("assets").with_leaves(["logo.png"]),
+    ///     Tree::new("lib.rs"),
+    /// ]);
+    /// tree.retain(|label| label.ends_with(".rs"));
+    /// assert_eq!(tree.to_string(), "src\n├── bin\n│   └── main.rs\n└── lib.rs\n");
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&D) -> bool,
+    {
+        self.retain_with(&mut f);
+    }
+
+    /// Whether the node stays, because it matches or one of its descendants does.
+    fn retain_with<F>(&mut self, f: &mut F) -> bool
+    where
+        F: FnMut(&D) -> bool,
+    {
+        let matches = f(&self.root);
+        self.leaves.retain_mut(|leaf| leaf.retain_with(f));
+        matches || !self.leaves.is_empty()
+    }
+
+    /// Removes the nodes whose label matches, along with everything below them. The root is
+    /// never removed, and the leaves of a removed node aren't looked at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let mut tree = Tree::new(".").with_leaves([
+    ///     Tree::new("target").with_leaves(["debug"]),
+    ///     Tree::new("src").with_leaves([".DS_Store", "lib.rs"]),
+    /// ]);
+    /// tree.prune(|label| *label == "target" || label.starts_with('.'));
+    /// assert_eq!(tree.to_string(), ".\n└── src\n    └── lib.rs\n");
+    /// ```
+    pub fn prune<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&D) -> bool,
+    {
+        self.prune_with(&mut f);
+    }
+
+    fn prune_with<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(&D) -> bool,
+    {
+        self.leaves.retain_mut(|leaf| {
+            let pruned = f(&leaf.root);
+            if !pruned {
+                leaf.prune_with(f);
+            }
+            !pruned
+        });
+    }
+}
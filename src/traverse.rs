@@ -0,0 +1,330 @@
+//! Pre-order, post-order and breadth-first walks over trees, each node coming with where it
+//! sits in the tree.
+
+use std::{collections::VecDeque, fmt::Display, slice};
+
+use crate::{Tree, TreeStyle};
+
+// ---------------------------------------------------------
+
+/// A node met by a traversal, and where it is.
+///
+/// # Examples
+///
+/// ```
+/// use treeleaf::Tree;
+///
+/// let tree = Tree::new("foo").with_leaves([Tree::new("bar").with_leaves(["baz"]), "qux".into()]);
+/// let baz = tree.pre_order().find(|visit| visit.node.root == "baz").unwrap();
+/// assert_eq!((baz.depth, baz.index, baz.is_last), (2, 0, true));
+/// assert_eq!(baz.path, [0, 0]);
+/// assert_eq!(tree.get(&baz.path[..1]).unwrap().root, "bar");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visit<T> {
+    /// `&Tree` for borrowing traversals, [`NodeMut`] for mutable ones.
+    pub node: T,
+    /// The root is at depth 0.
+    pub depth: usize,
+    /// Position among its siblings, 0 for the root.
+    pub index: usize,
+    /// Whether it's the last of its siblings, which the root is.
+    pub is_last: bool,
+    /// Sibling indices from the root down to the node, empty for the root. Ancestors are the
+    /// prefixes of the path, see [`Tree::get`].
+    pub path: Vec<usize>,
+}
+
+impl<T> Visit<T> {
+    fn root(node: T) -> Self {
+        Self { node, depth: 0, index: 0, is_last: true, path: Vec::new() }
+    }
+
+    /// The visit of child `index` of `count`.
+    fn child<U>(&self, node: U, index: usize, count: usize) -> Visit<U> {
+        let mut path = self.path.clone();
+        path.push(index);
+        Visit { node, depth: self.depth + 1, index, is_last: index + 1 == count, path }
+    }
+}
+
+/// The parts of a node that mutable traversals can change, the leaves being walked over.
+#[derive(Debug)]
+pub struct NodeMut<'a, D>
+where
+    D: Display,
+{
+    pub root: &'a mut D,
+    pub style: &'a mut Option<TreeStyle>,
+    pub annotation: &'a mut Option<String>,
+}
+
+/// Splits `tree` into what a [`NodeMut`] hands out and its leaves, so that both can be borrowed
+/// at once.
+fn split<D>(tree: &mut Tree<D>) -> (NodeMut<'_, D>, &mut Vec<Tree<D>>)
+where
+    D: Display,
+{
+    let Tree { root, leaves, style, annotation, .. } = tree;
+    (NodeMut { root, style, annotation }, leaves)
+}
+
+// ---------------------------------------------------------
+
+impl<D> Tree<D>
+where
+    D: Display,
+{
+    /// The node at `path`, sibling indices from the root down, the root itself for `&[]`.
+    pub fn get(&self, path: &[usize]) -> Option<&Tree<D>> {
+        path.iter().try_fold(self, |tree, &i| tree.leaves.get(i))
+    }
+
+    /// Mutable access to the node at `path`, like [`Tree::get`].
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Tree<D>> {
+        path.iter().try_fold(self, |tree, &i| tree.leaves.get_mut(i))
+    }
+
+    /// Walks the tree parents first, in the order it's displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new(1).with_leaves([Tree::new(2).with_leaves([3]), Tree::new(4)]);
+    /// let labels: Vec<_> = tree.pre_order().map(|visit| visit.node.root).collect();
+    /// assert_eq!(labels, [1, 2, 3, 4]);
+    /// ```
+    pub fn pre_order(&self) -> PreOrder<'_, D> {
+        PreOrder { stack: vec![Visit::root(self)] }
+    }
+
+    /// Walks the tree like [`Tree::pre_order`], with the labels, styles and annotations open to
+    /// changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let mut tree = Tree::new("foo".to_owned()).with_leaves(["bar".to_owned()]);
+    /// for visit in tree.pre_order_mut() {
+    ///     *visit.node.root = format!("{}{}", visit.depth, visit.node.root);
+    /// }
+    /// assert_eq!(tree.to_string(), "0foo\n└── 1bar\n");
+    /// ```
+    pub fn pre_order_mut(&mut self) -> PreOrderMut<'_, D> {
+        PreOrderMut { stack: vec![Visit::root(self)] }
+    }
+
+    /// Walks the tree children first, each node coming after all of its descendants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new(1).with_leaves([Tree::new(2).with_leaves([3]), Tree::new(4)]);
+    /// let labels: Vec<_> = tree.post_order().map(|visit| visit.node.root).collect();
+    /// assert_eq!(labels, [3, 2, 4, 1]);
+    /// ```
+    pub fn post_order(&self) -> PostOrder<'_, D> {
+        PostOrder { stack: vec![(Visit::root(self), 0)] }
+    }
+
+    /// Walks the tree like [`Tree::post_order`], with the labels, styles and annotations open
+    /// to changes.
+    pub fn post_order_mut(&mut self) -> PostOrderMut<'_, D> {
+        let (node, leaves) = split(self);
+        PostOrderMut { stack: vec![(Visit::root(node), leaves.len(), leaves.iter_mut())] }
+    }
+
+    /// Walks the tree level by level, from the root down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use treeleaf::Tree;
+    ///
+    /// let tree = Tree::new(1).with_leaves([Tree::new(2).with_leaves([3]), Tree::new(4)]);
+    /// let labels: Vec<_> = tree.breadth_first().map(|visit| visit.node.root).collect();
+    /// assert_eq!(labels, [1, 2, 4, 3]);
+    /// ```
+    pub fn breadth_first(&self) -> BreadthFirst<'_, D> {
+        BreadthFirst { queue: VecDeque::from([Visit::root(self)]) }
+    }
+
+    /// Walks the tree like [`Tree::breadth_first`], with the labels, styles and annotations
+    /// open to changes.
+    pub fn breadth_first_mut(&mut self) -> BreadthFirstMut<'_, D> {
+        BreadthFirstMut { queue: VecDeque::from([Visit::root(self)]) }
+    }
+}
+
+// ---------------------------------------------------------
+
+/// Iterator returned by [`Tree::pre_order`].
+pub struct PreOrder<'a, D>
+where
+    D: Display,
+{
+    stack: Vec<Visit<&'a Tree<D>>>,
+}
+
+impl<'a, D> Iterator for PreOrder<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<&'a Tree<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.stack.pop()?;
+        let count = visit.node.leaves.len();
+        for (i, leaf) in visit.node.leaves.iter().enumerate().rev() {
+            self.stack.push(visit.child(leaf, i, count));
+        }
+        Some(visit)
+    }
+}
+
+/// Iterator returned by [`Tree::pre_order_mut`].
+pub struct PreOrderMut<'a, D>
+where
+    D: Display,
+{
+    stack: Vec<Visit<&'a mut Tree<D>>>,
+}
+
+impl<'a, D> Iterator for PreOrderMut<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<NodeMut<'a, D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Visit { node, depth, index, is_last, path } = self.stack.pop()?;
+        let (node, leaves) = split(node);
+        let visit = Visit { node, depth, index, is_last, path };
+        let count = leaves.len();
+        for (i, leaf) in leaves.iter_mut().enumerate().rev() {
+            self.stack.push(visit.child(leaf, i, count));
+        }
+        Some(visit)
+    }
+}
+
+/// Iterator returned by [`Tree::post_order`].
+pub struct PostOrder<'a, D>
+where
+    D: Display,
+{
+    /// The ancestors of the next node, with how many of their leaves have been walked into.
+    stack: Vec<(Visit<&'a Tree<D>>, usize)>,
+}
+
+impl<'a, D> Iterator for PostOrder<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<&'a Tree<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (visit, next) = self.stack.last_mut()?;
+            let leaves = &visit.node.leaves;
+            match leaves.get(*next) {
+                Some(leaf) => {
+                    let child = visit.child(leaf, *next, leaves.len());
+                    *next += 1;
+                    self.stack.push((child, 0));
+                }
+                None => return self.stack.pop().map(|(visit, _)| visit),
+            }
+        }
+    }
+}
+
+/// Iterator returned by [`Tree::post_order_mut`].
+pub struct PostOrderMut<'a, D>
+where
+    D: Display,
+{
+    /// The ancestors of the next node.
+    stack: Vec<Ancestor<'a, D>>,
+}
+
+/// A node being walked into, with its number of leaves and the ones left.
+type Ancestor<'a, D> = (Visit<NodeMut<'a, D>>, usize, slice::IterMut<'a, Tree<D>>);
+
+impl<'a, D> Iterator for PostOrderMut<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<NodeMut<'a, D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (visit, count, leaves) = self.stack.last_mut()?;
+            let index = *count - leaves.len();
+            match leaves.next() {
+                Some(leaf) => {
+                    let (node, leaves) = split(leaf);
+                    let child = visit.child(node, index, *count);
+                    self.stack.push((child, leaves.len(), leaves.iter_mut()));
+                }
+                None => return self.stack.pop().map(|(visit, ..)| visit),
+            }
+        }
+    }
+}
+
+/// Iterator returned by [`Tree::breadth_first`].
+pub struct BreadthFirst<'a, D>
+where
+    D: Display,
+{
+    queue: VecDeque<Visit<&'a Tree<D>>>,
+}
+
+impl<'a, D> Iterator for BreadthFirst<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<&'a Tree<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.queue.pop_front()?;
+        let count = visit.node.leaves.len();
+        for (i, leaf) in visit.node.leaves.iter().enumerate() {
+            self.queue.push_back(visit.child(leaf, i, count));
+        }
+        Some(visit)
+    }
+}
+
+/// Iterator returned by [`Tree::breadth_first_mut`].
+pub struct BreadthFirstMut<'a, D>
+where
+    D: Display,
+{
+    queue: VecDeque<Visit<&'a mut Tree<D>>>,
+}
+
+impl<'a, D> Iterator for BreadthFirstMut<'a, D>
+where
+    D: Display,
+{
+    type Item = Visit<NodeMut<'a, D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Visit { node, depth, index, is_last, path } = self.queue.pop_front()?;
+        let (node, leaves) = split(node);
+        let visit = Visit { node, depth, index, is_last, path };
+        let count = leaves.len();
+        for (i, leaf) in leaves.iter_mut().enumerate() {
+            self.queue.push_back(visit.child(leaf, i, count));
+        }
+        Some(visit)
+    }
+}
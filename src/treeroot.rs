@@ -0,0 +1,111 @@
+//! `tree(1)`-like rendering of a directory on disk.
+
+use std::{
+    fs::{self, DirEntry},
+    io,
+    path::Path,
+};
+
+use crate::{Tree, TreeStyle};
+
+/// Returns the final component of the `Path`, if there is one.
+///
+/// If the path is a normal file, this is the file name. If it's the path of a directory, this
+/// is the directory name.
+///
+/// Falls back to the whole path when it terminates in `..` or is a filesystem root.
+fn label<P>(path: P) -> String
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Directories are bold and blue, like `ls --color`.
+fn dir_style() -> TreeStyle {
+    "bold.blue".parse().expect("valid style")
+}
+
+/// Sizes like `tree -h`: bytes up to 1023, then one decimal and a binary unit, e.g. `4.0K`.
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+    // Precision loss only matters way past the exabytes.
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1023.95 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", size, UNITS[unit])
+}
+
+/// Walks the directory at `path` and returns it as a [`Tree`] of file names.
+///
+/// Entries are sorted by file name so the output is stable across platforms.
+///
+/// # Errors
+///
+/// This function will return an error if `path` or any directory below it can't be read.
+pub fn tree<P>(path: P) -> io::Result<Tree<String>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut root = Tree::new(label(path.canonicalize()?)).with_style(dir_style());
+    push_entries(&mut root, path, false)?;
+    Ok(root)
+}
+
+/// Like [`tree`], with every entry annotated with its size like `tree -h`.
+///
+/// Directories are annotated with the total size of the files below them, like `du`.
+///
+/// # Errors
+///
+/// This function will return an error if `path` or any directory below it can't be read.
+pub fn tree_with_sizes<P>(path: P) -> io::Result<Tree<String>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut root = Tree::new(label(path.canonicalize()?)).with_style(dir_style());
+    let size = push_entries(&mut root, path, true)?;
+    Ok(root.with_annotation(human_size(size)))
+}
+
+// * `canonicalize` - Returns the canonical, absolute form of the path with all intermediate
+//   components normalized and symbolic links resolved.
+// * `metadata` - Returns the metadata for the file that this entry points at.
+//
+// Returns the total size of the files below `path`.
+fn push_entries(root: &mut Tree<String>, path: &Path, sizes: bool) -> io::Result<u64> {
+    let mut entries: Vec<DirEntry> = fs::read_dir(path)?.collect::<io::Result<_>>()?;
+    entries.sort_by_key(DirEntry::file_name);
+
+    let mut total = 0;
+    for entry in entries {
+        let mut leaf = Tree::new(label(entry.path()));
+        let size = if entry.file_type()?.is_dir() {
+            leaf = leaf.with_style(dir_style());
+            push_entries(&mut leaf, &entry.path(), sizes)?
+        } else if sizes {
+            entry.metadata()?.len()
+        } else {
+            0
+        };
+        if sizes {
+            leaf = leaf.with_annotation(human_size(size));
+        }
+        total += size;
+        root.push(leaf);
+    }
+    Ok(total)
+}
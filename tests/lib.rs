@@ -112,3 +112,399 @@ pub(crate) fn tempdir() -> tempfile::TempDir {
 //         })
 //         .run();
 // }
+
+// ---------------------------------------------------------
+
+use std::{
+    io::Write,
+    process::{Command, Output as ProcessOutput, Stdio},
+};
+
+fn treeleaf(args: &[&str], stdin: &str) -> ProcessOutput {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_treeleaf"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn treeleaf");
+    // Usage errors exit before reading stdin.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &ProcessOutput) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+const BINARY_TREE: &str = r#"{"value":"A","left":{"value":"B","left":null,"right":null},"right":{"value":"C","left":null,"right":null}}"#;
+
+#[test]
+fn cli_help() {
+    let output = treeleaf(&["--help"], "");
+    assert_eq!(output.status.code(), Some(0));
+    for subcommand in ["dir", "render", "wizard", "convert", "export"] {
+        assert!(stdout(&output).contains(subcommand), "missing `{}` in --help", subcommand);
+    }
+}
+
+#[test]
+fn cli_unknown_subcommand_is_usage_error() {
+    let output = treeleaf(&["frobnicate"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn cli_render_stdin() {
+    let output = treeleaf(&["render"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A\n├── B\n└── C\n");
+}
+
+#[test]
+fn cli_render_glyph_preset() {
+    let output = treeleaf(&["render", "--glyphs", "ascii"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A\n|-- B\n`-- C\n");
+
+    let output = treeleaf(&["render", "--glyphs", "fancy"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn cli_render_theme_and_indent() {
+    let dir = tempdir();
+    let theme = dir.path().join("theme.json");
+    std::fs::write(
+        &theme,
+        r#"{ "middle_item": "+", "last_item": "+", "item_indent": "= ", "skip_indent": "  " }"#,
+    )
+    .unwrap();
+
+    let output =
+        treeleaf(&["render", "--theme", theme.to_str().unwrap(), "--indent", "5"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A\n+=== B\n+=== C\n");
+
+    std::fs::write(&theme, r#"{ "item_indent": "= " }"#).unwrap();
+    let output = treeleaf(&["render", "--theme", theme.to_str().unwrap()], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn cli_render_colors() {
+    let output = treeleaf(&["render", "--color", "always", "--depth-styles", "red"], BINARY_TREE);
+    assert_eq!(stdout(&output), "A\n\u{1b}[31m├── \u{1b}[0mB\n\u{1b}[31m└── \u{1b}[0mC\n");
+
+    // Piped output isn't a terminal.
+    let output = treeleaf(&["render", "--depth-styles", "red"], BINARY_TREE);
+    assert_eq!(stdout(&output), "A\n├── B\n└── C\n");
+
+    let output = treeleaf(&["render", "--depth-styles", "red.sparkly"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn cli_render_limits() {
+    let output = treeleaf(&["render", "-L", "0"], BINARY_TREE);
+    assert_eq!(stdout(&output), "A\n└── [+ 2 descendants]\n");
+
+    let output = treeleaf(&["render", "--max-children", "1"], BINARY_TREE);
+    assert_eq!(stdout(&output), "A\n├── B\n└── … 1 more\n");
+}
+
+#[test]
+fn cli_render_wrap() {
+    let tree = r#"{"value":"A","left":{"value":"the quick brown fox","left":null,"right":null},"right":null}"#;
+    let output = treeleaf(&["render", "--wrap=14"], tree);
+    assert_eq!(stdout(&output), "A\n└── the quick\n    brown fox\n");
+
+    // Piped output has no terminal width to wrap to.
+    let output = treeleaf(&["render", "--wrap"], tree);
+    assert_eq!(stdout(&output), "A\n└── the quick brown fox\n");
+
+    // A bare `--wrap` leaves the next argument alone, and nothing fits in 0 columns.
+    let output = treeleaf(&["render", "--wrap", "-"], tree);
+    assert_eq!(stdout(&output), "A\n└── the quick brown fox\n");
+    let output = treeleaf(&["render", "--wrap=0"], tree);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn cli_render_truncate() {
+    let output =
+        treeleaf(&["render", "--truncate", "6"], BINARY_TREE.replace("\"B\"", "\"BBB\"").as_str());
+    assert_eq!(stdout(&output), "A\n├── B…\n└── C\n");
+}
+
+#[test]
+fn cli_dir_sizes() {
+    let dir = tempdir();
+    std::fs::create_dir(dir.path().join("bar")).unwrap();
+    std::fs::write(dir.path().join("bar").join("baz.md"), vec![b'x'; 2048]).unwrap();
+    std::fs::write(dir.path().join("foo.md"), "hello").unwrap();
+
+    let output = treeleaf(&["dir", "--sizes", dir.path().to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    let rendered = stdout(&output);
+    let (root, rest) = rendered.split_once('\n').unwrap();
+    assert!(root.ends_with("  2.0K"), "{}", root);
+    let width = root.chars().count();
+    assert_eq!(
+        rest,
+        format!(
+            "├── bar{0:>1$}\n│   └── baz.md{2:>3$}\n└── foo.md{4:>5$}\n",
+            "2.0K",
+            width - 7,
+            "2.0K",
+            width - 14,
+            "5",
+            width - 10
+        )
+    );
+}
+
+#[test]
+fn cli_render_invalid_input_fails() {
+    let output = treeleaf(&["render", "-"], "not json");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+}
+
+#[test]
+fn cli_export_json() {
+    let output = treeleaf(&["export", "--format", "json"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(value["right"]["value"], "C");
+}
+
+#[test]
+fn cli_export_mermaid() {
+    let output = treeleaf(&["export", "--format", "mermaid", "--direction", "LR"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "graph LR\n    n[\"A\"]\n    n --> nL[\"B\"]\n    n --> nR[\"C\"]\n"
+    );
+}
+
+#[test]
+fn cli_export_dot() {
+    let output = treeleaf(&["export", "--format", "dot", "--direction", "LR"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "digraph tree {\n    rankdir=LR;\n    ordering=out;\n    n [label=\"A\"];\n    \
+         nL [label=\"B\"];\n    n -> nL;\n    nR [label=\"C\"];\n    n -> nR;\n}\n"
+    );
+}
+
+#[test]
+fn cli_export_html() {
+    let output =
+        treeleaf(&["export", "--format", "html", "--glyphs", "ascii", "-L", "0"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    let html = stdout(&output);
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert!(html.contains("<li><details><summary class=\"node\"><span class=\"label\">A</span>"));
+    assert!(html.contains("<span class=\"glyphs\">`-- </span><span class=\"label\">C</span>"));
+    assert!(html.contains("<input type=\"search\""));
+}
+
+#[test]
+fn cli_export_svg() {
+    let output = treeleaf(&["export", "--format", "svg"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    let svg = stdout(&output);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""), "{}", svg);
+    assert_eq!(svg.matches("<rect").count(), 3);
+    assert_eq!(svg.matches("<path").count(), 2);
+
+    let output = treeleaf(&["export", "--format", "svg", "--max-children", "1"], BINARY_TREE);
+    let svg = stdout(&output);
+    assert_eq!(svg.matches("<rect").count(), 3);
+    assert!(svg.contains(">… 1 more</tspan>"), "{}", svg);
+}
+
+#[test]
+fn cli_render_from_mermaid() {
+    let markdown = "# Tree\n\n```mermaid\nR(1)\nR-->L1(2)\nR-->R1(3)\n```\n";
+    let output = treeleaf(&["render", "--from", "mermaid"], markdown);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "1\n├── 2\n└── 3\n");
+
+    let output = treeleaf(&["render", "--from", "mermaid"], "A-->B\nC-->B\n");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("line 2: `B` already has a parent"), "{}", stderr);
+}
+
+#[test]
+fn cli_mindmap() {
+    let mindmap = "mindmap\n  root((1))\n    2\n      ::icon(fa fa-book)\n    3\n";
+    let output = treeleaf(&["render", "--from", "mindmap"], mindmap);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "1\n├── 2\n└── 3\n");
+
+    // Mindmap nodes are exported by their labels, which `--from json` reads back.
+    let dir = tempdir();
+    let json = dir.path().join("mindmap.json");
+    let args = ["render", "--from", "mindmap", "--export-json", json.to_str().unwrap()];
+    assert_eq!(treeleaf(&args, mindmap).status.code(), Some(0));
+    let output = treeleaf(&["render", "--from", "json", json.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "1\n├── 2\n└── 3\n");
+
+    let output = treeleaf(&["export", "--format", "mindmap"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "mindmap\n  A\n    B\n    C\n");
+}
+
+#[test]
+fn cli_render_from_json() {
+    let json = r#"{
+        "root": "foo",
+        "leaves": [{ "root": "bar", "leaves": [{ "root": "baz" }] }, { "root": "qux" }],
+        "options": { "glyphs": { "middle_item": "+", "last_item": "`", "item_indent": "-- ",
+                                 "middle_skip": "|", "last_skip": " ", "skip_indent": "   " } }
+    }"#;
+    let output = treeleaf(&["render", "--from", "json"], json);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "foo\n+-- bar\n|   `-- baz\n`-- qux\n");
+
+    // Options on the command line win over the saved ones.
+    let output = treeleaf(&["render", "--from", "json", "--glyphs", "box", "-L", "1"], json);
+    assert_eq!(stdout(&output), "foo\n├── bar\n│   └── [+ 1 descendant]\n└── qux\n");
+
+    let output = treeleaf(&["render", "--from", "json"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("- is not a tree"));
+}
+
+#[test]
+fn cli_render_from_yaml_and_toml() {
+    let dir = tempdir();
+    let path = dir.path().join("config.yml");
+    std::fs::write(&path, "server:\n  port: 8080\n  hosts: [a, b]\n").unwrap();
+    let output = treeleaf(&["render", "--from", "yaml", path.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}\n└── server\n    ├── port: 8080\n    └── hosts\n        ├── [0]: a\n        └── [1]: b\n",
+            path.display()
+        )
+    );
+
+    let output = treeleaf(&["render", "--from", "toml"], "[server]\nport = 8080\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), ".\n└── server\n    └── port: 8080\n");
+
+    let output = treeleaf(&["render", "--from", "toml"], "port = \n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("-: invalid TOML"));
+}
+
+#[test]
+fn cli_json() {
+    let json = r#"{"name": "treeleaf", "tags": ["cli", "tree", "mermaid"], "license": null}"#;
+    let output = treeleaf(&["json"], json);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        ".\n├── name: \"treeleaf\"\n├── tags\n│   ├── [0]: \"cli\"\n│   ├── [1]: \"tree\"\n│   └── [2]: \"mermaid\"\n└── license: null\n"
+    );
+
+    let dir = tempdir();
+    let path = dir.path().join("payload.json");
+    std::fs::write(&path, json).unwrap();
+    let args = ["json", path.to_str().unwrap(), "--types", "--max-string", "4", "--max-items", "1"];
+    let output = treeleaf(&args, "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{} (object)\n├── name (string): \"tree…\"\n├── tags (array[3])\n│   ├── [0] (string): \"cli\"\n│   └── … 2 more items\n└── license (null): null\n",
+            path.display()
+        )
+    );
+
+    // A scalar document is shown like any other, its type and the file name included.
+    std::fs::write(&path, "\"x\"").unwrap();
+    let output = treeleaf(&["json", "-t", path.to_str().unwrap()], "");
+    assert_eq!(stdout(&output), format!("{} (string): \"x\"\n", path.display()));
+
+    let output = treeleaf(&["json"], "{");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("- is not JSON"));
+}
+
+#[test]
+fn cli_dir_export_svg() {
+    let dir = tempdir();
+    std::fs::create_dir(dir.path().join("bar")).unwrap();
+    std::fs::write(dir.path().join("foo.md"), "").unwrap();
+    let svg = dir.path().join("tree.svg");
+
+    let output =
+        treeleaf(&["dir", dir.path().to_str().unwrap(), "--export-svg", svg.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).ends_with("├── bar\n└── foo.md\n"), "{}", stdout(&output));
+    let svg = std::fs::read_to_string(svg).unwrap();
+    assert!(svg.contains(">foo.md</tspan>"), "{}", svg);
+    assert!(svg.contains("fill=\"blue\" font-weight=\"bold\"><tspan"), "{}", svg);
+}
+
+#[test]
+fn cli_export_markup_and_csv() {
+    let output = treeleaf(&["export", "--format", "markdown"], BINARY_TREE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "- A\n  - B\n  - C\n");
+
+    let output = treeleaf(
+        &["export", "--format", "asciidoc", "--markup", "code", "-g", "ascii"],
+        BINARY_TREE,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "----\nA\n|-- B\n`-- C\n----\n");
+
+    let dir = tempdir();
+    let [markdown, csv, json] = ["tree.md", "tree.csv", "tree.json"].map(|f| dir.path().join(f));
+    let output = treeleaf(
+        &[
+            "render",
+            "--markup",
+            "code",
+            "--export-markdown",
+            markdown.to_str().unwrap(),
+            "--export-csv",
+            csv.to_str().unwrap(),
+            "--export-json",
+            json.to_str().unwrap(),
+        ],
+        BINARY_TREE,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A\n├── B\n└── C\n");
+    let read = |path| std::fs::read_to_string(path).unwrap();
+    assert_eq!(read(&markdown), "```text\nA\n├── B\n└── C\n```\n");
+    assert_eq!(read(&csv), "id,parent_id,depth,path,label\n0,,0,A,A\n1,0,1,A/B,B\n2,0,1,A/C,C\n");
+
+    // The JSON export reads back as is.
+    let output = treeleaf(&["render", "--from", "json", json.to_str().unwrap()], "");
+    assert_eq!(stdout(&output), "A\n├── B\n└── C\n");
+}
+
+#[test]
+fn cli_dir() {
+    let dir = tempdir();
+    std::fs::create_dir(dir.path().join("bar")).unwrap();
+    std::fs::write(dir.path().join("bar").join("baz.md"), "").unwrap();
+    std::fs::write(dir.path().join("foo.md"), "").unwrap();
+
+    let output = treeleaf(&["dir", dir.path().to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    let rendered = stdout(&output);
+    assert_eq!(rendered.split_once('\n').unwrap().1, "├── bar\n│   └── baz.md\n└── foo.md\n");
+}